@@ -0,0 +1,20 @@
+// Compiled against the cbindgen header in CI to catch declarations C++ callers cannot use, such
+// as callback parameters emitted as `Option<...>`.
+#include "navkit-rpkg-lib.h"
+
+static void log_message(const char *) {}
+
+static void log_level_message(LogLevel, const char *, const char *, void *) {}
+
+static void report_progress(ProgressPhase, uintptr_t, uintptr_t, const char *) {}
+
+void check_callback_parameters(const PartitionManager *partition_manager) {
+    navkit_register_logger(log_level_message, nullptr, LogLevel::Info);
+    navkit_register_logger(nullptr, nullptr, LogLevel::Info);
+    load_hash_list("hash_list.txt", log_message);
+    load_hash_list("hash_list.txt", nullptr);
+    extract_scene_mesh_resources("nav.json", "Runtime", partition_manager, "output", "ALOC",
+                                 log_message, report_progress, nullptr);
+    extract_scene_mesh_resources("nav.json", "Runtime", partition_manager, "output", "ALOC",
+                                 nullptr, nullptr, nullptr);
+}
@@ -0,0 +1,2 @@
+pub mod resource_bundle;
+pub mod resource_bundle_writer;
\ No newline at end of file
@@ -0,0 +1,352 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::logging::logger::Logger;
+
+// Bundle layout, all integers little-endian:
+//   header: magic "NKRB", version u32, entry count u64, index offset u64
+//   resource data, one block per entry
+//   index: one entry per resource, sorted by hash:
+//     hash u64, type [u8; 4], flags u32, data offset u64, stored size u64, size u64
+pub const BUNDLE_MAGIC: &[u8; 4] = b"NKRB";
+pub const BUNDLE_VERSION: u32 = 1;
+pub const BUNDLE_HEADER_SIZE: u64 = 24;
+pub const BUNDLE_INDEX_ENTRY_SIZE: usize = 40;
+pub const BUNDLE_FLAG_LZ4: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleEntry {
+    pub hash: u64,
+    pub resource_type: String,
+    pub flags: u32,
+    pub offset: u64,
+    pub stored_size: u64,
+    pub size: u64,
+}
+
+impl BundleEntry {
+    pub fn is_compressed(&self) -> bool {
+        self.flags & BUNDLE_FLAG_LZ4 != 0
+    }
+
+    pub fn to_bytes(&self) -> [u8; BUNDLE_INDEX_ENTRY_SIZE] {
+        let mut bytes = [0u8; BUNDLE_INDEX_ENTRY_SIZE];
+        let mut resource_type = [b' '; 4];
+        for (target, source) in resource_type.iter_mut().zip(self.resource_type.bytes()) {
+            *target = source;
+        }
+        bytes[0..8].copy_from_slice(&self.hash.to_le_bytes());
+        bytes[8..12].copy_from_slice(&resource_type);
+        bytes[12..16].copy_from_slice(&self.flags.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.offset.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.stored_size.to_le_bytes());
+        bytes[32..40].copy_from_slice(&self.size.to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; BUNDLE_INDEX_ENTRY_SIZE]) -> Self {
+        let u64_at = |start: usize| u64::from_le_bytes(bytes[start..start + 8].try_into().unwrap());
+        Self {
+            hash: u64_at(0),
+            resource_type: String::from_utf8_lossy(&bytes[8..12])
+                .trim_end()
+                .to_string(),
+            flags: u32::from_le_bytes(bytes[12..16].try_into().unwrap()),
+            offset: u64_at(16),
+            stored_size: u64_at(24),
+            size: u64_at(32),
+        }
+    }
+}
+
+// Reads resources from a bundle written by `ResourceBundleWriter`. The index is loaded on open
+// and resource data is read on demand.
+pub struct ResourceBundle {
+    path: PathBuf,
+    entries: Vec<BundleEntry>,
+    file: Mutex<File>,
+}
+
+impl ResourceBundle {
+    pub fn open(path: &Path, logger: &Logger) -> Result<Self, NavKitError> {
+        let logger = logger.with_module(module_path!());
+        let invalid_bundle = |message: String| {
+            NavKitError::new(
+                NavKitErrorCode::InvalidBundle,
+                format!("Invalid resource bundle {}: {}", path.display(), message),
+            )
+            .log(&logger)
+        };
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::ResourceRead,
+                    format!("Failed to open resource bundle {}: {}", path.display(), e),
+                )
+                .log(&logger));
+            }
+        };
+        let file_len = file
+            .metadata()
+            .map_err(|e| invalid_bundle(format!("failed to read file size: {}", e)))?
+            .len();
+        let mut reader = BufReader::new(&file);
+
+        let mut header = [0u8; BUNDLE_HEADER_SIZE as usize];
+        reader
+            .read_exact(&mut header)
+            .map_err(|e| invalid_bundle(format!("failed to read header: {}", e)))?;
+        if &header[0..4] != BUNDLE_MAGIC {
+            return Err(invalid_bundle("wrong magic".to_string()));
+        }
+        let version = u32::from_le_bytes(header[4..8].try_into().unwrap());
+        if version != BUNDLE_VERSION {
+            return Err(invalid_bundle(format!("unsupported version {}", version)));
+        }
+        let entry_count = u64::from_le_bytes(header[8..16].try_into().unwrap());
+        let index_offset = u64::from_le_bytes(header[16..24].try_into().unwrap());
+        let index_end = entry_count
+            .checked_mul(BUNDLE_INDEX_ENTRY_SIZE as u64)
+            .and_then(|index_size| index_offset.checked_add(index_size));
+        if index_offset < BUNDLE_HEADER_SIZE || index_end.is_none_or(|end| end > file_len) {
+            return Err(invalid_bundle(format!(
+                "index of {} entries at offset {} does not fit in the file",
+                entry_count, index_offset
+            )));
+        }
+
+        reader
+            .seek(SeekFrom::Start(index_offset))
+            .map_err(|e| invalid_bundle(format!("failed to seek to index: {}", e)))?;
+        let mut entries = Vec::new();
+        for _ in 0..entry_count {
+            let mut entry_bytes = [0u8; BUNDLE_INDEX_ENTRY_SIZE];
+            reader
+                .read_exact(&mut entry_bytes)
+                .map_err(|e| invalid_bundle(format!("failed to read index: {}", e)))?;
+            let entry = BundleEntry::from_bytes(&entry_bytes);
+            // Entries are trusted by `read`, so a corrupt index must not lead to oversized
+            // allocations or sizes that wrap when handed to lz4.
+            let data_end = entry.offset.checked_add(entry.stored_size);
+            if entry.offset < BUNDLE_HEADER_SIZE || data_end.is_none_or(|end| end > index_offset) {
+                return Err(invalid_bundle(format!(
+                    "data of resource {:016X} lies outside the data section",
+                    entry.hash
+                )));
+            }
+            if entry.size > i32::MAX as u64 {
+                return Err(invalid_bundle(format!(
+                    "resource {:016X} is too large: {} bytes",
+                    entry.hash, entry.size
+                )));
+            }
+            entries.push(entry);
+        }
+        if !entries.windows(2).all(|pair| pair[0].hash < pair[1].hash) {
+            return Err(invalid_bundle("index is not sorted by hash".to_string()));
+        }
+        drop(reader);
+
+        logger.debug(format!(
+            "Opened resource bundle {} with {} resources.",
+            path.display(),
+            entries.len()
+        ));
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn entries(&self) -> &[BundleEntry] {
+        &self.entries
+    }
+
+    pub fn entry(&self, hash: u64) -> Option<&BundleEntry> {
+        self.entries
+            .binary_search_by_key(&hash, |entry| entry.hash)
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+
+    pub fn read(&self, hash: u64, logger: &Logger) -> Result<Vec<u8>, NavKitError> {
+        let logger = logger.with_module(module_path!());
+        let entry = match self.entry(hash) {
+            Some(entry) => entry,
+            None => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::UnknownHash,
+                    format!(
+                        "Resource {:016X} is not in bundle {}",
+                        hash,
+                        self.path.display()
+                    ),
+                )
+                .log(&logger));
+            }
+        };
+        let read_error = |e: std::io::Error| {
+            NavKitError::new(
+                NavKitErrorCode::ResourceRead,
+                format!(
+                    "Failed to read resource {:016X} from bundle {}: {}",
+                    hash,
+                    self.path.display(),
+                    e
+                ),
+            )
+            .log(&logger)
+        };
+        let mut stored = vec![0u8; entry.stored_size as usize];
+        {
+            let mut file = self.file.lock().unwrap();
+            file.seek(SeekFrom::Start(entry.offset))
+                .map_err(read_error)?;
+            file.read_exact(&mut stored).map_err(read_error)?;
+        }
+        if !entry.is_compressed() {
+            return Ok(stored);
+        }
+        lz4::block::decompress(&stored, Some(entry.size as i32)).map_err(read_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::resource_bundle_writer::{BundleData, ResourceBundleWriter};
+    use std::fs;
+
+    fn logger() -> Logger {
+        Logger::new(None, module_path!())
+    }
+
+    // Each test gets its own file so tests can run in parallel.
+    fn bundle_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "navkit-rpkg-lib-{}-{}.nkrb",
+            std::process::id(),
+            name
+        ))
+    }
+
+    // Writes a bundle with an LZ4 compressed, an incompressible and an empty resource, added out
+    // of hash order.
+    fn write_bundle(path: &Path) -> Vec<(u64, &'static str, Vec<u8>)> {
+        let resources = vec![
+            (0x00F5817876E4B2BB, "ALOC", b"navkit ".repeat(200)),
+            (0x0012345678ABCDEF, "PRIM", (0..=255u8).collect::<Vec<_>>()),
+            (0x00AAAAAAAAAAAAAA, "TEMP", Vec::new()),
+        ];
+        let mut writer = ResourceBundleWriter::create(path).unwrap();
+        for (hash, resource_type, contents) in &resources {
+            let data = BundleData::new(contents.clone(), true).unwrap();
+            assert!(writer.add(*hash, resource_type, data).unwrap());
+        }
+        assert!(!writer
+            .add(
+                resources[0].0,
+                "ALOC",
+                BundleData::new(Vec::new(), false).unwrap()
+            )
+            .unwrap());
+        writer.finish().unwrap();
+        resources
+    }
+
+    fn open_error(path: &Path, bytes: &[u8]) -> NavKitErrorCode {
+        fs::write(path, bytes).unwrap();
+        match ResourceBundle::open(path, &logger()) {
+            Ok(_) => NavKitErrorCode::Success,
+            Err(e) => e.code,
+        }
+    }
+
+    #[test]
+    fn round_trip_reads_back_every_resource() {
+        let path = bundle_path("round-trip");
+        let resources = write_bundle(&path);
+        let bundle = ResourceBundle::open(&path, &logger()).unwrap();
+        assert_eq!(bundle.entries().len(), resources.len());
+        assert!(bundle
+            .entries()
+            .windows(2)
+            .all(|pair| pair[0].hash < pair[1].hash));
+        for (hash, resource_type, contents) in &resources {
+            let entry = bundle.entry(*hash).unwrap();
+            assert_eq!(entry.resource_type, *resource_type);
+            assert_eq!(entry.size, contents.len() as u64);
+            assert_eq!(bundle.read(*hash, &logger()).unwrap(), *contents);
+        }
+        assert!(bundle.entry(0x00F5817876E4B2BB).unwrap().is_compressed());
+        assert!(!bundle.entry(0x0012345678ABCDEF).unwrap().is_compressed());
+        assert_eq!(
+            bundle.read(0x0011111111111111, &logger()).unwrap_err().code,
+            NavKitErrorCode::UnknownHash
+        );
+        drop(bundle);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn open_rejects_truncated_or_corrupt_bundles() {
+        let path = bundle_path("corrupt");
+        write_bundle(&path);
+        let bytes = fs::read(&path).unwrap();
+        let index_offset = u64::from_le_bytes(bytes[16..24].try_into().unwrap()) as usize;
+
+        assert_eq!(
+            open_error(&path, &bytes[..10]),
+            NavKitErrorCode::InvalidBundle
+        );
+        assert_eq!(
+            open_error(&path, &bytes[..bytes.len() - 1]),
+            NavKitErrorCode::InvalidBundle
+        );
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0..4].copy_from_slice(b"NOPE");
+        assert_eq!(
+            open_error(&path, &wrong_magic),
+            NavKitErrorCode::InvalidBundle
+        );
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[4..8].copy_from_slice(&(BUNDLE_VERSION + 1).to_le_bytes());
+        assert_eq!(
+            open_error(&path, &wrong_version),
+            NavKitErrorCode::InvalidBundle
+        );
+
+        let mut huge_entry_count = bytes.clone();
+        huge_entry_count[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            open_error(&path, &huge_entry_count),
+            NavKitErrorCode::InvalidBundle
+        );
+
+        let mut huge_stored_size = bytes.clone();
+        huge_stored_size[index_offset + 24..index_offset + 32]
+            .copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            open_error(&path, &huge_stored_size),
+            NavKitErrorCode::InvalidBundle
+        );
+
+        let mut huge_size = bytes.clone();
+        huge_size[index_offset + 32..index_offset + 40]
+            .copy_from_slice(&(i32::MAX as u64 + 1).to_le_bytes());
+        assert_eq!(
+            open_error(&path, &huge_size),
+            NavKitErrorCode::InvalidBundle
+        );
+
+        assert_eq!(open_error(&path, &bytes), NavKitErrorCode::Success);
+        fs::remove_file(&path).unwrap();
+    }
+}
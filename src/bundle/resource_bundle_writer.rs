@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::bundle::resource_bundle::{
+    BundleEntry, BUNDLE_FLAG_LZ4, BUNDLE_HEADER_SIZE, BUNDLE_MAGIC, BUNDLE_VERSION,
+};
+
+// Resource data ready to be appended to a bundle. Compression happens here so callers can
+// prepare entries in parallel and only hold the writer while appending.
+pub struct BundleData {
+    flags: u32,
+    size: u64,
+    stored: Vec<u8>,
+}
+
+impl BundleData {
+    pub fn new(contents: Vec<u8>, compress: bool) -> io::Result<Self> {
+        let size = contents.len() as u64;
+        if compress {
+            let compressed = lz4::block::compress(&contents, None, false)?;
+            if compressed.len() < contents.len() {
+                return Ok(Self {
+                    flags: BUNDLE_FLAG_LZ4,
+                    size,
+                    stored: compressed,
+                });
+            }
+        }
+        Ok(Self {
+            flags: 0,
+            size,
+            stored: contents,
+        })
+    }
+}
+
+// Writes a bundle to a temporary file that is renamed into place by `finish`. Dropping the
+// writer without finishing removes the temporary file.
+pub struct ResourceBundleWriter {
+    path: PathBuf,
+    temp_path: PathBuf,
+    file: Option<BufWriter<File>>,
+    position: u64,
+    entries: Vec<BundleEntry>,
+    hashes: HashSet<u64>,
+}
+
+impl ResourceBundleWriter {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut temp_path: OsString = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        let mut file = BufWriter::new(File::create(&temp_path)?);
+        file.write_all(&[0u8; BUNDLE_HEADER_SIZE as usize])?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            temp_path,
+            file: Some(file),
+            position: BUNDLE_HEADER_SIZE,
+            entries: Vec::new(),
+            hashes: HashSet::new(),
+        })
+    }
+
+    // Returns false if a resource with the same hash was already added.
+    pub fn add(&mut self, hash: u64, resource_type: &str, data: BundleData) -> io::Result<bool> {
+        if !self.hashes.insert(hash) {
+            return Ok(false);
+        }
+        let file = self.file.as_mut().expect("bundle writer already finished");
+        file.write_all(&data.stored)?;
+        self.entries.push(BundleEntry {
+            hash,
+            resource_type: resource_type.to_string(),
+            flags: data.flags,
+            offset: self.position,
+            stored_size: data.stored.len() as u64,
+            size: data.size,
+        });
+        self.position += data.stored.len() as u64;
+        Ok(true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        let mut file = self.file.take().expect("bundle writer already finished");
+        self.entries.sort_by_key(|entry| entry.hash);
+        for entry in &self.entries {
+            file.write_all(&entry.to_bytes())?;
+        }
+        let mut header = Vec::with_capacity(BUNDLE_HEADER_SIZE as usize);
+        header.extend_from_slice(BUNDLE_MAGIC);
+        header.extend_from_slice(&BUNDLE_VERSION.to_le_bytes());
+        header.extend_from_slice(&(self.entries.len() as u64).to_le_bytes());
+        header.extend_from_slice(&self.position.to_le_bytes());
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&header)?;
+        file.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&self.temp_path, &self.path)
+    }
+}
+
+impl Drop for ResourceBundleWriter {
+    fn drop(&mut self) {
+        drop(self.file.take());
+        if self.temp_path.exists() {
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod navkit_error;
\ No newline at end of file
@@ -0,0 +1,110 @@
+use crate::logging::logger::Logger;
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::fmt;
+use std::os::raw::c_char;
+
+// Values are part of the C ABI. Never renumber a code; add new ones at the end.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavKitErrorCode {
+    Success = 0,
+    NullArgument = 1,
+    MissingThumbsDat = 2,
+    InvalidThumbsDat = 3,
+    InvalidGameVersion = 4,
+    PackageDefinitionRead = 5,
+    PackageDefinitionParse = 6,
+    PartitionMount = 7,
+    NavJsonRead = 8,
+    NavJsonParse = 9,
+    InvalidHash = 10,
+    UnknownHash = 11,
+    PackageOpen = 12,
+    ResourceRead = 13,
+    DirectoryCreate = 14,
+    FileWrite = 15,
+    ThreadPanic = 16,
+    Cancelled = 17,
+    IndexOutOfRange = 18,
+    BufferTooSmall = 19,
+    RuntimeFolderNotFound = 20,
+    ThreadPoolCreate = 21,
+    InvalidResourceType = 22,
+    InvalidBundle = 23,
+    InvalidResourcePath = 24,
+    HashListRead = 25,
+    HashListNotLoaded = 26,
+    InvalidSearchPattern = 27,
+    NoHashes = 28,
+    InvalidPrim = 29,
+}
+
+#[derive(Debug, Clone)]
+pub struct NavKitError {
+    pub code: NavKitErrorCode,
+    pub message: String,
+}
+
+impl NavKitError {
+    pub fn new(code: NavKitErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn log(self, logger: &Logger) -> Self {
+        logger.error(&self.message);
+        self
+    }
+}
+
+impl fmt::Display for NavKitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for NavKitError {}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<(NavKitErrorCode, CString)>> = const { RefCell::new(None) };
+}
+
+pub fn set_last_error(error: &NavKitError) {
+    let message = CString::new(error.message.replace('\0', "")).unwrap();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some((error.code, message)));
+}
+
+pub fn clear_last_error() {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = None);
+}
+
+pub fn last_error_code() -> NavKitErrorCode {
+    LAST_ERROR.with(|last_error| match &*last_error.borrow() {
+        Some((code, _)) => *code,
+        None => NavKitErrorCode::Success,
+    })
+}
+
+// The returned pointer stays valid until the next error is recorded or cleared on this thread.
+pub fn last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last_error| match &*last_error.borrow() {
+        Some((_, message)) => message.as_ptr(),
+        None => std::ptr::null(),
+    })
+}
+
+pub fn to_error_code(result: Result<(), NavKitError>) -> NavKitErrorCode {
+    match result {
+        Ok(()) => {
+            clear_last_error();
+            NavKitErrorCode::Success
+        }
+        Err(e) => {
+            set_last_error(&e);
+            e.code
+        }
+    }
+}
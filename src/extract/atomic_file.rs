@@ -0,0 +1,25 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub struct AtomicFile;
+
+impl AtomicFile {
+    // Writes to a temporary file next to `path` and renames it into place, so readers and
+    // interrupted runs never see a partially written file.
+    pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+        let temp_path = Self::temp_path(path);
+        if let Err(e) = fs::write(&temp_path, contents).and_then(|_| fs::rename(&temp_path, path)) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn temp_path(path: &Path) -> PathBuf {
+        let mut temp_path: OsString = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        PathBuf::from(temp_path)
+    }
+}
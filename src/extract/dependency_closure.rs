@@ -0,0 +1,105 @@
+use std::collections::{HashSet, VecDeque};
+
+use rpkg_rs::resource::partition_manager::PartitionManager;
+use rpkg_rs::resource::resource_info::ResourceInfo;
+use rpkg_rs::resource::runtime_resource_id::RuntimeResourceID;
+
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::extract::dependency_options::DependencyOptions;
+use crate::extract::extraction_result::{ExtractionResult, ExtractionStatus};
+use crate::logging::logger::Logger;
+use crate::package::package_scan::PackageScan;
+use crate::package::resource_hash::ResourceHash;
+
+pub struct DependencyClosure {
+    // The resolved root hashes followed by every resource they reference.
+    pub hashes: Vec<String>,
+    // One failed outcome per root hash that could not be parsed or is not in any partition.
+    pub failures: ExtractionResult,
+}
+
+impl DependencyClosure {
+    // Collects the root hashes followed by every resource they reference, directly or
+    // transitively, in breadth-first order. Roots that cannot be resolved are recorded in
+    // `failures` and the closure of the remaining roots is still collected. References that are
+    // not present in any mounted partition are logged and left out.
+    pub fn collect(
+        root_hashes: &[String],
+        partition_manager: &PartitionManager,
+        dependency_options: &DependencyOptions,
+        logger: &Logger,
+    ) -> DependencyClosure {
+        let logger = logger.with_module(module_path!());
+        let mut visited: HashSet<RuntimeResourceID> = HashSet::new();
+        let mut queue: VecDeque<(RuntimeResourceID, ResourceInfo, usize)> = VecDeque::new();
+        let mut hashes: Vec<String> = Vec::new();
+        let mut failures = ExtractionResult::default();
+
+        for hash in root_hashes {
+            let rrid = match ResourceHash::parse(hash) {
+                Ok(rrid) => rrid,
+                Err(e) => {
+                    failures.push_failure(hash.clone(), e.log(&logger));
+                    continue;
+                }
+            };
+            if visited.contains(&rrid) {
+                continue;
+            }
+            match PackageScan::get_resource_info(partition_manager, &rrid) {
+                Some(info) => {
+                    visited.insert(rrid);
+                    queue.push_back((rrid, info.last_occurrence, 0));
+                }
+                None => failures.push_failure(
+                    hash.clone(),
+                    NavKitError::new(
+                        NavKitErrorCode::UnknownHash,
+                        format!(
+                            "Error getting resource info for hash: {}",
+                            ResourceHash::describe(&rrid)
+                        ),
+                    )
+                    .log(&logger),
+                ),
+            }
+        }
+
+        while let Some((rrid, resource_info, depth)) = queue.pop_front() {
+            hashes.push(rrid.to_hex_string());
+            if depth >= dependency_options.max_depth {
+                continue;
+            }
+            for (reference, _) in resource_info.references() {
+                if !reference.is_valid() || visited.contains(reference) {
+                    continue;
+                }
+                visited.insert(*reference);
+                let reference_info =
+                    match PackageScan::get_resource_info(partition_manager, reference) {
+                        Some(info) => info.last_occurrence,
+                        None => {
+                            logger.warn(format!(
+                                "Skipping reference {} of {}: not found in any partition.",
+                                ResourceHash::describe(reference),
+                                ResourceHash::describe(&rrid)
+                            ));
+                            continue;
+                        }
+                    };
+                if !dependency_options.includes_type(&reference_info.data_type()) {
+                    continue;
+                }
+                queue.push_back((*reference, reference_info, depth + 1));
+            }
+        }
+
+        logger.info(format!(
+            "Found {} resources in the dependency closure of {} root resources. Failed to resolve {} root resources.",
+            hashes.len(),
+            root_hashes.len(),
+            failures.count(ExtractionStatus::Failed)
+        ));
+        DependencyClosure { hashes, failures }
+    }
+}
@@ -0,0 +1,28 @@
+#[derive(Debug, Clone)]
+pub struct DependencyOptions {
+    // Number of reference levels followed from the root resources. 0 only returns the roots.
+    pub max_depth: usize,
+    // Resource types such as "MATI" or "TEXT". References of other types are neither returned
+    // nor followed. `None` follows every type.
+    pub included_types: Option<Vec<String>>,
+}
+
+impl Default for DependencyOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            included_types: None,
+        }
+    }
+}
+
+impl DependencyOptions {
+    pub fn includes_type(&self, resource_type: &str) -> bool {
+        match &self.included_types {
+            Some(included_types) => included_types
+                .iter()
+                .any(|included_type| included_type.eq_ignore_ascii_case(resource_type)),
+            None => true,
+        }
+    }
+}
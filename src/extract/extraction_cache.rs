@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::extract::atomic_file::AtomicFile;
+use crate::extract::extraction_manifest::ManifestEntry;
+use crate::logging::logger::Logger;
+
+pub const CACHE_INDEX_FILE_NAME: &str = ".navkit-cache.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    pub partition: String,
+    pub patch_id: usize,
+    pub size: u32,
+    pub md5: String,
+}
+
+// Remembers which package, patch and content each output file was extracted from, so a file is
+// only skipped when its source is unchanged and its contents on disk still match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractionCache {
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl ExtractionCache {
+    // A missing or unreadable index is treated as empty, which re-extracts everything.
+    pub fn load(output_folder: &Path, logger: &Logger) -> Self {
+        let logger = logger.with_module(module_path!());
+        let cache_path = output_folder.join(CACHE_INDEX_FILE_NAME);
+        let cache_json = match fs::read_to_string(&cache_path) {
+            Ok(json) => json,
+            Err(_) => return Self::default(),
+        };
+        match serde_json::from_str(&cache_json) {
+            Ok(cache) => cache,
+            Err(e) => {
+                logger.warn(format!(
+                    "Ignoring invalid extraction cache index {}: {}",
+                    cache_path.display(),
+                    e
+                ));
+                Self::default()
+            }
+        }
+    }
+
+    // Returns the checksum of `file_path` if it was extracted from `partition`/`patch_id` and
+    // still has the expected size and contents.
+    pub fn fresh_checksum(
+        &self,
+        file_path: &Path,
+        partition: &str,
+        patch_id: usize,
+        size: u32,
+    ) -> Option<String> {
+        let file_name = file_path.file_name()?.to_string_lossy();
+        let entry = self.entries.get(file_name.as_ref())?;
+        if entry.partition != partition || entry.patch_id != patch_id || entry.size != size {
+            return None;
+        }
+        let contents = fs::read(file_path).ok()?;
+        if contents.len() != size as usize {
+            return None;
+        }
+        let checksum = ManifestEntry::checksum(&contents);
+        (checksum == entry.md5).then_some(checksum)
+    }
+
+    pub fn update(&mut self, manifest_entries: &[ManifestEntry]) {
+        for manifest_entry in manifest_entries {
+            self.entries.insert(
+                manifest_entry.hash.clone() + "." + &manifest_entry.resource_type,
+                CacheEntry {
+                    partition: manifest_entry.partition.clone(),
+                    patch_id: manifest_entry.patch_id,
+                    size: manifest_entry.size,
+                    md5: manifest_entry.md5.clone(),
+                },
+            );
+        }
+    }
+
+    pub fn save(&self, output_folder: &Path, logger: &Logger) -> Result<(), NavKitError> {
+        let logger = logger.with_module(module_path!());
+        let cache_path = output_folder.join(CACHE_INDEX_FILE_NAME);
+        let cache_json = match serde_json::to_string(self) {
+            Ok(json) => json,
+            Err(e) => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::FileWrite,
+                    format!("Failed to serialize extraction cache index: {}", e),
+                )
+                .log(&logger));
+            }
+        };
+        if let Err(e) = AtomicFile::write(&cache_path, cache_json) {
+            return Err(NavKitError::new(
+                NavKitErrorCode::FileWrite,
+                format!(
+                    "Failed to write extraction cache index {}: {}",
+                    cache_path.display(),
+                    e
+                ),
+            )
+            .log(&logger));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use std::path::Path;
+
+use md5::{Digest, Md5};
+use rpkg_rs::resource::resource_partition::PatchId;
+use serde::{Deserialize, Serialize};
+
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::extract::atomic_file::AtomicFile;
+use crate::logging::logger::Logger;
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ManifestEntryStatus {
+    Extracted,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    pub hash: String,
+    // Only known when a hash list is loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub resource_type: String,
+    pub partition: String,
+    // 0 for the base package, otherwise the patch number.
+    pub patch_id: usize,
+    pub size: u32,
+    pub md5: String,
+    pub status: ManifestEntryStatus,
+}
+
+impl ManifestEntry {
+    pub fn patch_number(patch_id: PatchId) -> usize {
+        match patch_id {
+            PatchId::Base => 0,
+            PatchId::Patch(patch) => patch,
+        }
+    }
+
+    pub fn checksum(contents: &[u8]) -> String {
+        Md5::digest(contents)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionManifest {
+    pub resources: Vec<ManifestEntry>,
+}
+
+impl ExtractionManifest {
+    pub fn new(mut resources: Vec<ManifestEntry>) -> Self {
+        resources.sort_by(|a, b| a.hash.cmp(&b.hash));
+        Self { resources }
+    }
+
+    pub fn write(&self, output_folder: &Path, logger: &Logger) -> Result<(), NavKitError> {
+        let logger = logger.with_module(module_path!());
+        let manifest_path = output_folder.join(MANIFEST_FILE_NAME);
+        let manifest_json = match serde_json::to_string_pretty(self) {
+            Ok(json) => json,
+            Err(e) => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::FileWrite,
+                    format!("Failed to serialize extraction manifest: {}", e),
+                )
+                .log(&logger));
+            }
+        };
+        if let Err(e) = AtomicFile::write(&manifest_path, manifest_json) {
+            return Err(NavKitError::new(
+                NavKitErrorCode::FileWrite,
+                format!(
+                    "Failed to write extraction manifest {}: {}",
+                    manifest_path.display(),
+                    e
+                ),
+            )
+            .log(&logger));
+        }
+        logger.debug(format!(
+            "Wrote extraction manifest with {} resources to {}.",
+            self.resources.len(),
+            manifest_path.display()
+        ));
+        Ok(())
+    }
+}
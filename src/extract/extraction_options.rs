@@ -0,0 +1,43 @@
+use crate::geometry::mesh_filter::MeshFilterOptions;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    // One `<hash>.<TYPE>` file per resource.
+    LooseFiles,
+    // A single indexed bundle file holding every resource, see `ResourceBundle`.
+    Bundle,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExtractionOptions {
+    // Number of worker threads. 0 lets rayon pick one thread per logical CPU.
+    pub thread_count: usize,
+    // Writes `manifest.json` to the output folder, describing every requested resource.
+    pub write_manifest: bool,
+    // Writes an RPKG Tool compatible `<hash>.<TYPE>.meta.json` next to every resource. Only
+    // used with `OutputMode::LooseFiles`.
+    pub write_meta_json: bool,
+    pub output_mode: OutputMode,
+    // Name of the bundle file inside the output folder when using `OutputMode::Bundle`.
+    pub bundle_file_name: String,
+    // Compresses bundled resources with LZ4 when that makes them smaller.
+    pub compress_bundle: bool,
+    // When extracting scene meshes, skips meshes outside the scene's include boxes or inside its
+    // exclude boxes. `None` extracts every mesh.
+    pub mesh_filter: Option<MeshFilterOptions>,
+}
+
+impl Default for ExtractionOptions {
+    fn default() -> Self {
+        Self {
+            thread_count: 0,
+            write_manifest: true,
+            write_meta_json: false,
+            output_mode: OutputMode::LooseFiles,
+            bundle_file_name: "resources.nkb".to_string(),
+            compress_bundle: true,
+            mesh_filter: None,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractionStatus {
+    Extracted,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExtractionOutcome {
+    pub hash: String,
+    pub status: ExtractionStatus,
+    // Set when `status` is `Failed`.
+    pub error: Option<NavKitError>,
+}
+
+impl ExtractionOutcome {
+    pub fn error_code(&self) -> NavKitErrorCode {
+        match &self.error {
+            Some(error) => error.code,
+            None => NavKitErrorCode::Success,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExtractionResult {
+    pub outcomes: Vec<ExtractionOutcome>,
+}
+
+impl ExtractionResult {
+    pub fn push(&mut self, hash: impl Into<String>, status: ExtractionStatus) {
+        self.outcomes.push(ExtractionOutcome {
+            hash: hash.into(),
+            status,
+            error: None,
+        });
+    }
+
+    pub fn push_failure(&mut self, hash: impl Into<String>, error: NavKitError) {
+        self.outcomes.push(ExtractionOutcome {
+            hash: hash.into(),
+            status: ExtractionStatus::Failed,
+            error: Some(error),
+        });
+    }
+
+    pub fn with_status(
+        &self,
+        status: ExtractionStatus,
+    ) -> impl Iterator<Item = &ExtractionOutcome> {
+        self.outcomes
+            .iter()
+            .filter(move |outcome| outcome.status == status)
+    }
+
+    pub fn count(&self, status: ExtractionStatus) -> usize {
+        self.with_status(status).count()
+    }
+
+    // Turns the result into an error describing the first failure, for callers that only want
+    // to know whether every resource was extracted.
+    pub fn into_error(self) -> Result<(), NavKitError> {
+        let failed = self.count(ExtractionStatus::Failed);
+        match self.outcomes.into_iter().find_map(|outcome| outcome.error) {
+            Some(first_error) => Err(NavKitError::new(
+                first_error.code,
+                format!(
+                    "Failed to extract {} resources. First failure: {}",
+                    failed, first_error.message
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+}
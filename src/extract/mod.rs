@@ -1 +1,9 @@
+pub mod atomic_file;
+pub mod dependency_closure;
+pub mod dependency_options;
+pub mod extraction_cache;
+pub mod extraction_manifest;
+pub mod extraction_options;
+pub mod extraction_result;
+pub mod resource_meta;
 pub mod rpkg_extraction;
\ No newline at end of file
@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use rpkg_rs::resource::resource_info::ResourceInfo;
+use serde::{Deserialize, Serialize};
+
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::extract::atomic_file::AtomicFile;
+use crate::logging::logger::Logger;
+
+pub const META_FILE_EXTENSION: &str = ".meta.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceMetaReference {
+    pub hash: String,
+    // The reference flags byte in the current (HM3) layout, as two hex digits.
+    pub flag: String,
+}
+
+// Sidecar metadata in the `.meta.json` layout written and read by RPKG Tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceMeta {
+    pub hash_value: String,
+    pub hash_offset: u64,
+    // Compressed size, or 0 when stored uncompressed. The high bit marks scrambled data.
+    pub hash_size: u32,
+    pub hash_resource_type: String,
+    pub hash_reference_table_size: u32,
+    pub hash_reference_table_dummy: u32,
+    pub hash_size_final: u32,
+    pub hash_size_in_memory: u32,
+    pub hash_size_in_video_memory: u32,
+    pub hash_reference_data: Vec<ResourceMetaReference>,
+}
+
+impl ResourceMeta {
+    pub fn from_resource_info(resource_info: &ResourceInfo) -> Self {
+        let compressed_size = resource_info.compressed_size().unwrap_or(0) as u32;
+        let scrambled_flag = if resource_info.is_scrambled() {
+            0x80000000
+        } else {
+            0
+        };
+        Self {
+            hash_value: resource_info.rrid().to_hex_string(),
+            hash_offset: resource_info.data_offset(),
+            hash_size: compressed_size | scrambled_flag,
+            hash_resource_type: resource_info.data_type(),
+            hash_reference_table_size: resource_info.reference_chunk_size() as u32,
+            hash_reference_table_dummy: 0,
+            hash_size_final: resource_info.size(),
+            hash_size_in_memory: resource_info.system_memory_requirement(),
+            hash_size_in_video_memory: resource_info.video_memory_requirement(),
+            hash_reference_data: resource_info
+                .references()
+                .iter()
+                .map(|(rrid, flags)| {
+                    let flag = flags.language_code()
+                        | ((flags.is_acquired() as u8) << 5)
+                        | ((flags.reference_type() as u8) << 6);
+                    ResourceMetaReference {
+                        hash: rrid.to_hex_string(),
+                        flag: format!("{:02X}", flag),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn write(&self, resource_file_path: &Path, logger: &Logger) -> Result<(), NavKitError> {
+        let logger = logger.with_module(module_path!());
+        let mut meta_file_path = resource_file_path.as_os_str().to_owned();
+        meta_file_path.push(META_FILE_EXTENSION);
+        let meta_json = match serde_json::to_string(self) {
+            Ok(json) => json,
+            Err(e) => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::FileWrite,
+                    format!("Failed to serialize meta for {}: {}", self.hash_value, e),
+                )
+                .log(&logger));
+            }
+        };
+        if let Err(e) = AtomicFile::write(Path::new(&meta_file_path), meta_json) {
+            return Err(NavKitError::new(
+                NavKitErrorCode::FileWrite,
+                format!("Meta file failed to be written: {}", e),
+            )
+            .log(&logger));
+        }
+        Ok(())
+    }
+}
@@ -1,220 +1,612 @@
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use rpkg_rs::resource::{
-    partition_manager::PartitionManager, resource_package::ResourcePackage,
+    partition_manager::PartitionManager, resource_info::ResourceInfo,
+    resource_package::ResourcePackage, resource_partition::PatchId,
     runtime_resource_id::RuntimeResourceID,
 };
-use std::ffi::CStr;
-use std::os::raw::c_char;
-use std::slice;
-use std::thread::{self};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fs,
+    panic::{self, AssertUnwindSafe},
     path::{Path, PathBuf},
+    sync::Mutex,
 };
 
-use crate::{json_serde::entities_json::EntitiesJson, package::package_scan::PackageScan};
+use crate::bundle::resource_bundle_writer::{BundleData, ResourceBundleWriter};
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::extract::atomic_file::AtomicFile;
+use crate::extract::dependency_closure::DependencyClosure;
+use crate::extract::dependency_options::DependencyOptions;
+use crate::extract::extraction_cache::ExtractionCache;
+use crate::extract::extraction_manifest::{ExtractionManifest, ManifestEntry, ManifestEntryStatus};
+use crate::extract::extraction_options::{ExtractionOptions, OutputMode};
+use crate::extract::extraction_result::{ExtractionResult, ExtractionStatus};
+use crate::extract::resource_meta::ResourceMeta;
+use crate::logging::logger::Logger;
+use crate::package::resource_hash::ResourceHash;
+use crate::package::resource_type::ResourceType;
+use crate::progress::cancellation_token::CancellationToken;
+use crate::progress::progress_report::{ProgressCallback, ProgressPhase, ProgressReport};
+use crate::{
+    json_serde::entities_json::{EntitiesJson, MeshHashesAndEntity},
+    package::package_scan::PackageScan,
+};
+
+struct ExtractionJob {
+    hash: String,
+    rrid: RuntimeResourceID,
+    resource_type: String,
+    patch_id: PatchId,
+    resource_info: ResourceInfo,
+}
 
 pub struct RpkgExtraction;
 
 impl RpkgExtraction {
-    pub unsafe fn extract_resources_from_rpkg(
+    #[allow(clippy::too_many_arguments)]
+    pub fn extract_resources_from_rpkg(
         runtime_folder: String,
-        needed_hashes: *const *const c_char,
-        needed_hashes_len: usize,
+        needed_hashes: &[String],
         partition_manager: &PartitionManager,
         output_folder: String,
         resource_type: String,
-        log_callback: extern "C" fn(*const c_char),
-    ) -> std::ffi::c_int {
-        let mut needed_hashes_list = Vec::new();
-        if !needed_hashes.is_null() {
-            let slice = slice::from_raw_parts(needed_hashes, needed_hashes_len);
-            for &ptr in slice {
-                if !ptr.is_null() {
-                    if let Ok(s) = CStr::from_ptr(ptr).to_str() {
-                        needed_hashes_list.push(s.to_string());
-                    }
-                }
-            }
+        logger: &Logger,
+        progress_callback: Option<ProgressCallback>,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> Result<ExtractionResult, NavKitError> {
+        Self::extract_resources_from_rpkg_with_options(
+            runtime_folder,
+            needed_hashes,
+            partition_manager,
+            output_folder,
+            resource_type,
+            &ExtractionOptions::default(),
+            logger,
+            progress_callback,
+            cancellation_token,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn extract_resources_from_rpkg_with_options(
+        runtime_folder: String,
+        needed_hashes: &[String],
+        partition_manager: &PartitionManager,
+        output_folder: String,
+        resource_type: String,
+        extraction_options: &ExtractionOptions,
+        logger: &Logger,
+        progress_callback: Option<ProgressCallback>,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> Result<ExtractionResult, NavKitError> {
+        let logger = logger.with_module(module_path!());
+        if needed_hashes.is_empty() {
+            return Err(NavKitError::new(
+                NavKitErrorCode::NoHashes,
+                "No hashes were given to extract",
+            )
+            .log(&logger));
         }
-        let resource_count = needed_hashes_list.len();
-        let target_num_threads = 10;
-        let output_folder_ref = &output_folder;
-        let runtime_folder_ref = &runtime_folder;
-        let resource_type_ref = &resource_type;
-        let msg = std::ffi::CString::new(format!(
+        let resource_type = ResourceType::parse(&resource_type, &logger)?;
+        ResourceType::check_mounted(&resource_type, partition_manager, &logger)?;
+        let mut extraction_result = ExtractionResult::default();
+        let resources_by_package = Self::group_resources_by_package(
+            needed_hashes,
+            Some(&resource_type),
+            partition_manager,
+            &mut extraction_result,
+            &logger,
+        );
+        Self::extract_grouped_resources(
+            runtime_folder,
+            resources_by_package,
+            extraction_result,
+            output_folder,
+            extraction_options,
+            &logger,
+            progress_callback,
+            cancellation_token,
+        )
+    }
+
+    // Extracts `root_hashes` and every resource they reference. Each resource is written with
+    // its own type as the file extension.
+    #[allow(clippy::too_many_arguments)]
+    pub fn extract_dependency_closure_from_rpkg(
+        runtime_folder: String,
+        root_hashes: &[String],
+        partition_manager: &PartitionManager,
+        output_folder: String,
+        dependency_options: &DependencyOptions,
+        extraction_options: &ExtractionOptions,
+        logger: &Logger,
+        progress_callback: Option<ProgressCallback>,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> Result<ExtractionResult, NavKitError> {
+        let logger = logger.with_module(module_path!());
+        let closure =
+            DependencyClosure::collect(root_hashes, partition_manager, dependency_options, &logger);
+        CancellationToken::check(cancellation_token).map_err(|e| e.log(&logger))?;
+        let mut extraction_result = closure.failures;
+        let resources_by_package = Self::group_resources_by_package(
+            &closure.hashes,
+            None,
+            partition_manager,
+            &mut extraction_result,
+            &logger,
+        );
+        Self::extract_grouped_resources(
+            runtime_folder,
+            resources_by_package,
+            extraction_result,
+            output_folder,
+            extraction_options,
+            &logger,
+            progress_callback,
+            cancellation_token,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn extract_grouped_resources(
+        runtime_folder: String,
+        resources_by_package: BTreeMap<String, Vec<ExtractionJob>>,
+        mut extraction_result: ExtractionResult,
+        output_folder: String,
+        extraction_options: &ExtractionOptions,
+        logger: &Logger,
+        progress_callback: Option<ProgressCallback>,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> Result<ExtractionResult, NavKitError> {
+        let logger = *logger;
+        logger.debug(format!(
             "Creating directory '{}' if it doesn't exist.",
             output_folder
-        ))
-        .unwrap();
-        log_callback(msg.as_ptr());
-        if let Err(e) = fs::create_dir_all(output_folder_ref) {
-            let msg =
-                std::ffi::CString::new(format!("Failed to create resource folder: {}", e)).unwrap();
-            log_callback(msg.as_ptr());
-            return -1;
+        ));
+        if let Err(e) = fs::create_dir_all(&output_folder) {
+            return Err(NavKitError::new(
+                NavKitErrorCode::DirectoryCreate,
+                format!("Failed to create resource folder: {}", e),
+            )
+            .log(&logger));
         }
-        let msg = std::ffi::CString::new(format!(
-            "Extracting using {} threads of around {} resources per thread...",
-            target_num_threads,
-            resource_count / target_num_threads
-        ))
-        .unwrap();
-        log_callback(msg.as_ptr());
-        let alocs_or_prims_output_folder_path = PathBuf::from(&output_folder);
-        let alocs_or_prims_output_folder_path_ref = &alocs_or_prims_output_folder_path;
-        let success = thread::scope(|scope| {
-            let mut handles = Vec::new();
-            for (chunk_i, chunk) in needed_hashes_list
-                .chunks(resource_count.div_ceil(target_num_threads))
-                .enumerate()
-            {
-                handles.push(scope.spawn(move || {
-                    let output_folder_path =
-                        PathBuf::from(String::from(output_folder_ref));
-                    let mut resource_packages: HashMap<String, ResourcePackage> = HashMap::new();
 
-                    let mut skipped = 0;
-                    let mut extracted = 0;
-                    for hash in chunk {
-                        let runtime_folder_path = PathBuf::from(runtime_folder_ref);
+        let resource_count: usize = resources_by_package.values().map(Vec::len).sum();
+
+        let thread_pool = match ThreadPoolBuilder::new()
+            .num_threads(extraction_options.thread_count)
+            .build()
+        {
+            Ok(pool) => pool,
+            Err(e) => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::ThreadPoolCreate,
+                    format!("Failed to create extraction thread pool: {}", e),
+                )
+                .log(&logger));
+            }
+        };
+        let thread_count = thread_pool.current_num_threads();
+        logger.info(format!(
+            "Extracting {} resources from {} packages using {} threads...",
+            resource_count,
+            resources_by_package.len(),
+            thread_count
+        ));
+
+        let runtime_folder_path = PathBuf::from(&runtime_folder);
+        let output_folder_path = PathBuf::from(&output_folder);
+        let mut extraction_cache = ExtractionCache::load(&output_folder_path, &logger);
+        let bundle_writer = match extraction_options.output_mode {
+            OutputMode::LooseFiles => None,
+            OutputMode::Bundle => {
+                let bundle_path = output_folder_path.join(&extraction_options.bundle_file_name);
+                match ResourceBundleWriter::create(&bundle_path) {
+                    Ok(writer) => Some(Mutex::new(writer)),
+                    Err(e) => {
+                        return Err(NavKitError::new(
+                            NavKitErrorCode::FileWrite,
+                            format!(
+                                "Failed to create resource bundle {}: {}",
+                                bundle_path.display(),
+                                e
+                            ),
+                        )
+                        .log(&logger));
+                    }
+                }
+            }
+        };
+        let progress = ProgressReport::new(
+            progress_callback,
+            ProgressPhase::ExtractResources,
+            resource_count,
+        );
+
+        // Each package is parsed once and shared by every worker. Work units never span packages
+        // and stay in offset order so reads from one package are as sequential as possible.
+        let resource_packages = Self::install_catching_panics(&thread_pool, &logger, || {
+            resources_by_package
+                .par_iter()
+                .map(|(package_name, _)| {
+                    let rpkg = ResourcePackage::from_file(&runtime_folder_path.join(package_name))
+                        .map_err(|e| {
+                            NavKitError::new(
+                                NavKitErrorCode::PackageOpen,
+                                format!("Failed parse resource package {}: {}", package_name, e),
+                            )
+                            .log(&logger)
+                        });
+                    (package_name.clone(), rpkg)
+                })
+                .collect::<HashMap<String, Result<ResourcePackage, NavKitError>>>()
+        })?;
+        let chunk_size = resource_count.div_ceil(thread_count).max(1);
+        let work_units = resources_by_package
+            .iter()
+            .flat_map(|(package_name, resources)| {
+                resources
+                    .chunks(chunk_size)
+                    .map(move |chunk| (package_name, chunk))
+            })
+            .collect::<Vec<_>>();
+
+        let results = Self::install_catching_panics(&thread_pool, &logger, || {
+            work_units
+                .par_iter()
+                .map(|(package_name, resources)| {
+                    let package_path = runtime_folder_path.join(package_name.as_str());
+                    let rpkg = &resource_packages[package_name.as_str()];
 
-                        let rrid: RuntimeResourceID =
-                            match RuntimeResourceID::from_hex_string(hash.as_str()) {
-                                Ok(id) => id,
-                                Err(_) => {
-                                    let msg = std::ffi::CString::new(format!(
-                                        "Error getting RRID from hash: {}",
-                                        hash.as_str()
-                                    ))
-                                    .unwrap();
-                                    log_callback(msg.as_ptr());
-                                    return Err(());
-                                }
-                            };
-                        let resource_info = match
-                            PackageScan::get_resource_info(partition_manager, &rrid) {
-                                Some(info) => info,
-                                None => {
-                                    let msg = std::ffi::CString::new(format!(
-                                        "Error getting resource info for hash: {}",
-                                        hash.as_str()
-                                    ))
-                                    .unwrap();
-                                    log_callback(msg.as_ptr());
-                                    return Err(());
-                                }
+                    let mut unit_outcomes = Vec::with_capacity(resources.len());
+                    for resource in resources.iter() {
+                        CancellationToken::check(cancellation_token)?;
+                        let outcome = match rpkg {
+                            Ok(rpkg) => Self::extract_resource(
+                                resource,
+                                rpkg,
+                                &package_path,
+                                package_name,
+                                &output_folder_path,
+                                &extraction_cache,
+                                bundle_writer.as_ref(),
+                                extraction_options,
+                                &logger,
+                                cancellation_token,
+                            ),
+                            Err(e) => Err(e.clone()),
                         };
-                        let last_partition = resource_info.last_partition;
-                        let package_path_buf = runtime_folder_path.join(last_partition.clone());
-                        let package_path = Path::new(&package_path_buf);
-                        let aloc_or_prim_file_path_buf =
-                            alocs_or_prims_output_folder_path_ref.join(hash.clone() + "." + &resource_type_ref);
-                        let aloc_or_prim_file_path_buf = aloc_or_prim_file_path_buf.as_os_str().to_str().unwrap();
-                        let aloc_or_prim_file_path = Path::new(aloc_or_prim_file_path_buf);
-                        if aloc_or_prim_file_path.exists() {
-                            let aloc_or_prim_file_path_metadata = aloc_or_prim_file_path.metadata();
-                            if aloc_or_prim_file_path_metadata.unwrap().modified().unwrap() >= package_path.metadata().unwrap().modified().unwrap() {
-                                skipped += 1;
-                                continue
-                            }
+                        match outcome {
+                            Err(e) if e.code == NavKitErrorCode::Cancelled => return Err(e),
+                            outcome => unit_outcomes.push((resource.hash.clone(), outcome)),
                         }
-                        let rpkg = match resource_packages.entry(last_partition.clone()) {
-                            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
-                            std::collections::hash_map::Entry::Vacant(entry) => {
-                                match ResourcePackage::from_file(&package_path_buf) {
-                                    Ok(pkg) => entry.insert(pkg),
-                                    Err(e) => {
-                                        let msg = std::ffi::CString::new(format!(
-                                            "Failed parse resource package: {}",
-                                            e
-                                        ))
-                                        .unwrap();
-                                        log_callback(msg.as_ptr());
-                                        return Err(());
-                                    }
-                                }
-                            }
-                        };
-                        let resource_contents = match rpkg.read_resource(&package_path_buf, &rrid) {
-                            Ok(c) => c,
-                            Err(e) => {
-                                let msg = std::ffi::CString::new(format!(
-                                    "Failed extract resource: {}",
-                                    e
-                                ))
-                                .unwrap();
-                                log_callback(msg.as_ptr());
-                                return Err(());
-                            }
-                        };
+                        progress.advance(package_name);
+                    }
+                    Ok(unit_outcomes)
+                })
+                .collect::<Vec<_>>()
+        })?;
 
-                        let file_extension: String;
-                        if resource_type_ref == "ALOC" {
-                            file_extension = ".ALOC".to_string();
-                        } else if resource_type_ref == "PRIM" {
-                            file_extension = ".PRIM".to_string();
-                        } else if resource_type_ref == "NAVP" {
-                            file_extension = ".NAVP".to_string();
-                        } else {
-                            file_extension = ".AIRG".to_string();
+        let mut manifest_entries = Vec::with_capacity(resource_count);
+        let mut cancelled = None;
+        for unit_result in results {
+            match unit_result {
+                Ok(unit_outcomes) => {
+                    for (hash, outcome) in unit_outcomes {
+                        match outcome {
+                            Ok(manifest_entry) => {
+                                extraction_result.push(
+                                    hash,
+                                    match manifest_entry.status {
+                                        ManifestEntryStatus::Extracted => {
+                                            ExtractionStatus::Extracted
+                                        }
+                                        ManifestEntryStatus::Skipped => ExtractionStatus::Skipped,
+                                    },
+                                );
+                                manifest_entries.push(manifest_entry);
+                            }
+                            Err(e) => extraction_result.push_failure(hash, e),
                         }
-                        let resource_file_path_buf =
-                            output_folder_path.join(hash.clone() + &file_extension);
-                        let resource_file_path =
-                            resource_file_path_buf.as_os_str().to_str().unwrap();
-                        extracted += 1;
+                    }
+                }
+                Err(e) => cancelled = Some(e),
+            }
+        }
+        logger.info(format!(
+            "Extracted {} resources. Skipped extraction of {} resources that are already up to date. Failed to extract {} resources.",
+            extraction_result.count(ExtractionStatus::Extracted),
+            extraction_result.count(ExtractionStatus::Skipped),
+            extraction_result.count(ExtractionStatus::Failed)
+        ));
+        let cache_result = match bundle_writer {
+            Some(_) => Ok(()),
+            None => {
+                extraction_cache.update(&manifest_entries);
+                extraction_cache.save(&output_folder_path, &logger)
+            }
+        };
+        if let Some(e) = cancelled {
+            return Err(e.log(&logger));
+        }
+        cache_result?;
+        if let Some(bundle_writer) = bundle_writer {
+            let bundle_writer = bundle_writer.into_inner().unwrap();
+            let bundled_count = bundle_writer.len();
+            if let Err(e) = bundle_writer.finish() {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::FileWrite,
+                    format!("Failed to write resource bundle: {}", e),
+                )
+                .log(&logger));
+            }
+            logger.info(format!(
+                "Wrote {} resources to bundle {}.",
+                bundled_count,
+                output_folder_path
+                    .join(&extraction_options.bundle_file_name)
+                    .display()
+            ));
+        }
+        if extraction_options.write_manifest {
+            ExtractionManifest::new(manifest_entries).write(&output_folder_path, &logger)?;
+        }
+        Ok(extraction_result)
+    }
 
-                        if let Err(e) = fs::write(resource_file_path, resource_contents) {
-                            let msg =
-                                std::ffi::CString::new(format!("File failed to be written: {}", e))
-                                    .unwrap();
-                            log_callback(msg.as_ptr());
-                            return Err(());
-                        }
+    // Rayon re-raises a worker panic on the calling thread. Catch it here so a panic in a package
+    // decoder never unwinds across the C ABI.
+    fn install_catching_panics<T: Send>(
+        thread_pool: &ThreadPool,
+        logger: &Logger,
+        work: impl FnOnce() -> T + Send,
+    ) -> Result<T, NavKitError> {
+        panic::catch_unwind(AssertUnwindSafe(|| thread_pool.install(work))).map_err(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            NavKitError::new(
+                NavKitErrorCode::ThreadPanic,
+                format!("Extraction thread panicked: {}", message),
+            )
+            .log(logger)
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn extract_resource(
+        resource: &ExtractionJob,
+        rpkg: &ResourcePackage,
+        package_path: &Path,
+        package_name: &str,
+        output_folder_path: &Path,
+        extraction_cache: &ExtractionCache,
+        bundle_writer: Option<&Mutex<ResourceBundleWriter>>,
+        extraction_options: &ExtractionOptions,
+        logger: &Logger,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> Result<ManifestEntry, NavKitError> {
+        let hash = &resource.hash;
+        let resource_type = &resource.resource_type;
+        let resource_file_path = output_folder_path.join(hash.clone() + "." + resource_type);
+        let patch_id = ManifestEntry::patch_number(resource.patch_id);
+        let size = resource.resource_info.size();
+        // Bundles are always written from scratch, so the cache only applies to loose files.
+        let cached_checksum = match bundle_writer {
+            Some(_) => None,
+            None => {
+                extraction_cache.fresh_checksum(&resource_file_path, package_name, patch_id, size)
+            }
+        };
+        let (md5, status) = match cached_checksum {
+            Some(checksum) => (checksum, ManifestEntryStatus::Skipped),
+            None => {
+                let resource_contents = match rpkg.read_resource(package_path, &resource.rrid) {
+                    Ok(c) => c,
+                    Err(e) => {
+                        return Err(NavKitError::new(
+                            NavKitErrorCode::ResourceRead,
+                            format!(
+                                "Failed extract resource {}: {}",
+                                ResourceHash::describe(&resource.rrid),
+                                e
+                            ),
+                        )
+                        .log(logger));
                     }
-                    let msg = std::ffi::CString::new(format!(
-                        "Thread {}: Extracted {} resources. Skipped extraction of {} resources that are newer than their rpkg file.",
-                        chunk_i, extracted, skipped)
-                    ).unwrap();
-                    log_callback(msg.as_ptr());
+                };
 
-                    Ok(())
-                }));
+                CancellationToken::check(cancellation_token)?;
+                let checksum = ManifestEntry::checksum(&resource_contents);
+                if let Some(bundle_writer) = bundle_writer {
+                    let bundle_error = |e: std::io::Error| {
+                        NavKitError::new(
+                            NavKitErrorCode::FileWrite,
+                            format!(
+                                "Failed to add resource {} to bundle: {}",
+                                ResourceHash::describe(&resource.rrid),
+                                e
+                            ),
+                        )
+                        .log(logger)
+                    };
+                    let data =
+                        BundleData::new(resource_contents, extraction_options.compress_bundle)
+                            .map_err(bundle_error)?;
+                    bundle_writer
+                        .lock()
+                        .unwrap()
+                        .add(ResourceHash::to_u64(&resource.rrid), resource_type, data)
+                        .map_err(bundle_error)?;
+                } else if let Err(e) = AtomicFile::write(&resource_file_path, resource_contents) {
+                    return Err(NavKitError::new(
+                        NavKitErrorCode::FileWrite,
+                        format!(
+                            "File {} failed to be written: {}",
+                            resource_file_path.display(),
+                            e
+                        ),
+                    )
+                    .log(logger));
+                }
+                (checksum, ManifestEntryStatus::Extracted)
             }
+        };
+        if extraction_options.write_meta_json && bundle_writer.is_none() {
+            ResourceMeta::from_resource_info(&resource.resource_info)
+                .write(&resource_file_path, logger)?;
+        }
+        Ok(ManifestEntry {
+            hash: hash.clone(),
+            path: ResourceHash::path_of(&resource.rrid),
+            resource_type: resource_type.clone(),
+            partition: package_name.to_string(),
+            patch_id,
+            size,
+            md5,
+            status,
+        })
+    }
 
-            let mut success = true;
-            for thread_handle in handles {
-                if let Ok(res) = thread_handle.join() {
-                    if res.is_err() {
-                        success = false;
-                    }
-                } else {
-                    success = false;
+    // When `resource_type` is set, resources of any other type are rejected. Hashes that cannot
+    // be resolved or have the wrong type are recorded as failures in `extraction_result`.
+    fn group_resources_by_package(
+        needed_hashes: &[String],
+        resource_type: Option<&str>,
+        partition_manager: &PartitionManager,
+        extraction_result: &mut ExtractionResult,
+        logger: &Logger,
+    ) -> BTreeMap<String, Vec<ExtractionJob>> {
+        let mut seen_rrids: HashSet<RuntimeResourceID> = HashSet::new();
+        let mut resources_by_package: BTreeMap<String, Vec<ExtractionJob>> = BTreeMap::new();
+        for hash in needed_hashes {
+            let rrid = match ResourceHash::parse(hash) {
+                Ok(rrid) => rrid,
+                Err(e) => {
+                    extraction_result.push_failure(hash, e.log(logger));
+                    continue;
                 }
+            };
+            if !seen_rrids.insert(rrid) {
+                continue;
             }
-            success
-        });
+            let resource_info = match PackageScan::get_resource_info(partition_manager, &rrid) {
+                Some(info) => info,
+                None => {
+                    extraction_result.push_failure(
+                        hash,
+                        NavKitError::new(
+                            NavKitErrorCode::UnknownHash,
+                            format!(
+                                "Error getting resource info for hash: {}",
+                                ResourceHash::describe(&rrid)
+                            ),
+                        )
+                        .log(logger),
+                    );
+                    continue;
+                }
+            };
+            let data_type = resource_info.last_occurrence.data_type();
+            if let Some(resource_type) = resource_type {
+                if data_type != resource_type {
+                    extraction_result.push_failure(
+                        hash,
+                        NavKitError::new(
+                            NavKitErrorCode::InvalidResourceType,
+                            format!(
+                                "Resource {} is a {} resource, not {}",
+                                ResourceHash::describe(&rrid),
+                                data_type,
+                                resource_type
+                            ),
+                        )
+                        .log(logger),
+                    );
+                    continue;
+                }
+            }
+            resources_by_package
+                .entry(resource_info.last_partition)
+                .or_default()
+                .push(ExtractionJob {
+                    hash: ResourceHash::output_name(hash, &rrid),
+                    rrid,
+                    resource_type: data_type,
+                    patch_id: resource_info.last_patch_id,
+                    resource_info: resource_info.last_occurrence,
+                });
+        }
+        for resources in resources_by_package.values_mut() {
+            resources.sort_by_key(|resource| resource.resource_info.data_offset());
+        }
+        resources_by_package
+    }
 
-        if success {
-            0
-        } else {
-            -1
+    pub fn read_resource_from_rpkg(
+        runtime_folder: String,
+        hash: &str,
+        partition_manager: &PartitionManager,
+        logger: &Logger,
+    ) -> Result<Vec<u8>, NavKitError> {
+        let logger = logger.with_module(module_path!());
+        let rrid = ResourceHash::parse(hash).map_err(|e| e.log(&logger))?;
+        let resource_info = match PackageScan::get_resource_info(partition_manager, &rrid) {
+            Some(info) => info,
+            None => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::UnknownHash,
+                    format!(
+                        "Error getting resource info for hash: {}",
+                        ResourceHash::describe(&rrid)
+                    ),
+                )
+                .log(&logger));
+            }
+        };
+        let package_path_buf = PathBuf::from(runtime_folder).join(resource_info.last_partition);
+        let rpkg = match ResourcePackage::from_file(&package_path_buf) {
+            Ok(pkg) => pkg,
+            Err(e) => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::PackageOpen,
+                    format!("Failed parse resource package: {}", e),
+                )
+                .log(&logger));
+            }
+        };
+        match rpkg.read_resource(&package_path_buf, &rrid) {
+            Ok(contents) => Ok(contents),
+            Err(e) => Err(NavKitError::new(
+                NavKitErrorCode::ResourceRead,
+                format!("Failed extract resource: {}", e),
+            )
+            .log(&logger)),
         }
     }
 
     pub fn get_needed_aloc_or_prim_hashes_from_scene(
         scene_nav_json: &EntitiesJson,
         aloc_or_prim_type: String,
+    ) -> HashSet<String> {
+        Self::get_needed_aloc_or_prim_hashes_from_meshes(&scene_nav_json.meshes, aloc_or_prim_type)
+    }
+
+    pub fn get_needed_aloc_or_prim_hashes_from_meshes<'a>(
+        meshes: impl IntoIterator<Item = &'a MeshHashesAndEntity>,
+        aloc_or_prim_type: String,
     ) -> HashSet<String> {
         let mut aloc_or_prim_hashes: HashSet<String> = HashSet::new();
         let mut needed_hashes: HashSet<String> = HashSet::new();
 
-        for entity in &scene_nav_json.meshes {
+        for entity in meshes {
             if aloc_or_prim_type == "ALOC" {
-                aloc_or_prim_hashes.insert(entity.aloc_hash.clone());
+                aloc_or_prim_hashes.insert(entity.aloc_hash.to_hex_string());
             } else {
-                aloc_or_prim_hashes.insert(entity.prim_hash.clone());
+                aloc_or_prim_hashes.insert(entity.prim_hash.to_hex_string());
             }
         }
         for hash in aloc_or_prim_hashes {
@@ -226,8 +618,42 @@ impl RpkgExtraction {
     pub fn get_all_resources_hashes_by_type_from_rpkg_files(
         partition_manager: &PartitionManager,
         resource_type: String,
-        log_callback: extern "C" fn(*const c_char),
+        logger: &Logger,
     ) -> Vec<String> {
+        Self::get_all_resource_ids_by_type_from_rpkg_files(partition_manager, resource_type, logger)
+            .iter()
+            .map(|rrid| rrid.to_hex_string())
+            .collect()
+    }
+
+    // Pairs each hash with its path from the loaded hash list, if any.
+    pub fn get_all_resources_hashes_and_paths_by_type_from_rpkg_files(
+        partition_manager: &PartitionManager,
+        resource_type: String,
+        logger: &Logger,
+    ) -> Vec<(String, Option<String>)> {
+        Self::get_all_resource_ids_by_type_from_rpkg_files(partition_manager, resource_type, logger)
+            .iter()
+            .map(|rrid| (rrid.to_hex_string(), ResourceHash::path_of(rrid)))
+            .collect()
+    }
+
+    pub fn get_all_resource_hash_values_by_type_from_rpkg_files(
+        partition_manager: &PartitionManager,
+        resource_type: String,
+        logger: &Logger,
+    ) -> Vec<u64> {
+        Self::get_all_resource_ids_by_type_from_rpkg_files(partition_manager, resource_type, logger)
+            .iter()
+            .map(ResourceHash::to_u64)
+            .collect()
+    }
+
+    pub fn get_all_resource_ids_by_type_from_rpkg_files(
+        partition_manager: &PartitionManager,
+        resource_type: String,
+        logger: &Logger,
+    ) -> Vec<RuntimeResourceID> {
         let navps: Vec<_> = partition_manager
             .partitions()
             .iter()
@@ -241,22 +667,16 @@ impl RpkgExtraction {
                     .collect::<Vec<_>>()
             })
             .collect();
-        let mut resource_hashes: HashSet<String> = HashSet::new();
+        let mut resource_hashes: HashSet<RuntimeResourceID> = HashSet::new();
         navps.iter().for_each(|resource| {
-            let rid = resource.rrid();
-            resource_hashes.insert(rid.to_hex_string());
+            resource_hashes.insert(*resource.rrid());
         });
 
-        let msg = std::ffi::CString::new(
-            format!(
-                "Found {} {} Resources in Rpkg files.",
-                resource_hashes.len(),
-                resource_type
-            )
-            .to_string(),
-        )
-        .unwrap();
-        log_callback(msg.as_ptr());
+        logger.with_module(module_path!()).info(format!(
+            "Found {} {} Resources in Rpkg files.",
+            resource_hashes.len(),
+            resource_type
+        ));
         resource_hashes.into_iter().collect()
     }
 }
@@ -0,0 +1,131 @@
+use crate::geometry::matrix4::Matrix4;
+use crate::json_serde::entities_json::Vec3;
+
+// An axis-aligned box. The empty box has `min` at +infinity and `max` at -infinity, so extending
+// it by any point yields that point.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl BoundingBox {
+    pub const EMPTY: BoundingBox = BoundingBox {
+        min: Vec3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        max: Vec3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+    };
+
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_center_and_half_extents(center: Vec3, half_extents: Vec3) -> Self {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Vec3>) -> Self {
+        points
+            .into_iter()
+            .fold(Self::EMPTY, |bounds, point| bounds.extended(point))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn extended(&self, point: &Vec3) -> Self {
+        Self::new(self.min.min(point), self.max.max(point))
+    }
+
+    pub fn union(&self, other: &BoundingBox) -> Self {
+        Self::new(self.min.min(&other.min), self.max.max(&other.max))
+    }
+
+    pub fn contains_point(&self, point: &Vec3) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+            && self.min.z <= other.max.z
+            && other.min.z <= self.max.z
+    }
+
+    // The smallest axis-aligned box around this box after `transform`.
+    pub fn transformed(&self, transform: &Matrix4) -> Self {
+        if self.is_empty() {
+            return Self::EMPTY;
+        }
+        let center = transform.transform_point(&self.center());
+        let half_extents = self.half_extents();
+        let world_half_extents = transform.axis(0).abs() * half_extents.x
+            + transform.axis(1).abs() * half_extents.y
+            + transform.axis(2).abs() * half_extents.z;
+        Self::from_center_and_half_extents(center, world_half_extents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::test_support::{assert_near, NO_ROTATION};
+    use crate::json_serde::entities_json::Rotation;
+
+    fn unit_cube() -> BoundingBox {
+        BoundingBox::from_center_and_half_extents(Vec3::ZERO, Vec3::ONE)
+    }
+
+    #[test]
+    fn transformed_translates_and_scales() {
+        let transform = Matrix4::from_transform(
+            &Vec3::new(10.0, 0.0, -5.0),
+            &NO_ROTATION,
+            &Vec3::new(2.0, -3.0, 1.0),
+        );
+        let bounds = unit_cube().transformed(&transform);
+        assert_near(bounds.min, Vec3::new(8.0, -3.0, -6.0));
+        assert_near(bounds.max, Vec3::new(12.0, 3.0, -4.0));
+    }
+
+    #[test]
+    fn transformed_encloses_rotated_corners() {
+        // 45 degrees about +Z.
+        let angle = std::f64::consts::FRAC_PI_8;
+        let transform = Matrix4::from_rotation(&Rotation {
+            x: 0.0,
+            y: 0.0,
+            z: angle.sin(),
+            w: angle.cos(),
+        });
+        let bounds = unit_cube().transformed(&transform);
+        let diagonal = std::f64::consts::SQRT_2;
+        assert_near(bounds.min, Vec3::new(-diagonal, -diagonal, -1.0));
+        assert_near(bounds.max, Vec3::new(diagonal, diagonal, 1.0));
+    }
+
+    #[test]
+    fn transformed_keeps_empty_boxes_empty() {
+        let transform = Matrix4::from_transform(&Vec3::ONE, &NO_ROTATION, &Vec3::ONE);
+        assert!(BoundingBox::EMPTY.transformed(&transform).is_empty());
+    }
+}
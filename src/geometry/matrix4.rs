@@ -0,0 +1,217 @@
+use std::ops::Mul;
+
+use crate::json_serde::entities_json::{Rotation, Vec3};
+
+// A row-major affine transform for column vectors: a point `p` maps to `m * p`, so the
+// translation is the last column.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix4 {
+    pub m: [[f64; 4]; 4],
+}
+
+impl Matrix4 {
+    pub const IDENTITY: Matrix4 = Matrix4 {
+        m: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    };
+
+    // Builds the world matrix of a Glacier entity: the scale is applied first, then the rotation,
+    // then the translation. The quaternion is normalized first; a zero or non-finite one is
+    // treated as no rotation.
+    pub fn from_transform(position: &Vec3, rotation: &Rotation, scale: &Vec3) -> Self {
+        let axes = Self::rotation_axes(rotation);
+        let scale = [scale.x, scale.y, scale.z];
+        let position = [position.x, position.y, position.z];
+        let mut m = Self::IDENTITY.m;
+        for row in 0..3 {
+            for column in 0..3 {
+                m[row][column] = axes[row][column] * scale[column];
+            }
+            m[row][3] = position[row];
+        }
+        Matrix4 { m }
+    }
+
+    pub fn from_rotation(rotation: &Rotation) -> Self {
+        Self::from_transform(&Vec3::ZERO, rotation, &Vec3::ONE)
+    }
+
+    pub fn transform_point(&self, point: &Vec3) -> Vec3 {
+        self.transform_vector(point) + self.translation()
+    }
+
+    // Applies rotation and scale, but not translation.
+    pub fn transform_vector(&self, vector: &Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * vector.x + m[0][1] * vector.y + m[0][2] * vector.z,
+            m[1][0] * vector.x + m[1][1] * vector.y + m[1][2] * vector.z,
+            m[2][0] * vector.x + m[2][1] * vector.y + m[2][2] * vector.z,
+        )
+    }
+
+    pub fn translation(&self) -> Vec3 {
+        Vec3::new(self.m[0][3], self.m[1][3], self.m[2][3])
+    }
+
+    // The images of the local X, Y and Z axes, including scale.
+    pub fn axis(&self, column: usize) -> Vec3 {
+        Vec3::new(self.m[0][column], self.m[1][column], self.m[2][column])
+    }
+
+    fn rotation_axes(rotation: &Rotation) -> [[f64; 3]; 3] {
+        let length = (rotation.x * rotation.x
+            + rotation.y * rotation.y
+            + rotation.z * rotation.z
+            + rotation.w * rotation.w)
+            .sqrt();
+        if length == 0.0 || !length.is_finite() {
+            return [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        }
+        let (x, y, z, w) = (
+            rotation.x / length,
+            rotation.y / length,
+            rotation.z / length,
+            rotation.w / length,
+        );
+        [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - z * w),
+                2.0 * (x * z + y * w),
+            ],
+            [
+                2.0 * (x * y + z * w),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - x * w),
+            ],
+            [
+                2.0 * (x * z - y * w),
+                2.0 * (y * z + x * w),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ]
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Matrix4;
+
+    fn mul(self, other: Matrix4) -> Matrix4 {
+        let mut m = [[0.0; 4]; 4];
+        for (row, values) in m.iter_mut().enumerate() {
+            for (column, value) in values.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[row][k] * other.m[k][column]).sum();
+            }
+        }
+        Matrix4 { m }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::test_support::{assert_near, NO_ROTATION};
+
+    // 90 degrees about +Z, as (x, y, z, w).
+    const QUARTER_TURN_Z: Rotation = Rotation {
+        x: 0.0,
+        y: 0.0,
+        z: std::f64::consts::FRAC_1_SQRT_2,
+        w: std::f64::consts::FRAC_1_SQRT_2,
+    };
+
+    #[test]
+    fn identity_quaternion_has_w_last() {
+        assert_eq!(Matrix4::from_rotation(&NO_ROTATION), Matrix4::IDENTITY);
+    }
+
+    #[test]
+    fn quaternion_rotates_counter_clockwise_about_its_axis() {
+        let rotation = Matrix4::from_rotation(&QUARTER_TURN_Z);
+        assert_near(rotation.axis(0), Vec3::new(0.0, 1.0, 0.0));
+        assert_near(rotation.axis(1), Vec3::new(-1.0, 0.0, 0.0));
+        assert_near(rotation.axis(2), Vec3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn quaternion_is_normalized() {
+        let doubled = Rotation {
+            x: QUARTER_TURN_Z.x * 2.0,
+            y: QUARTER_TURN_Z.y * 2.0,
+            z: QUARTER_TURN_Z.z * 2.0,
+            w: QUARTER_TURN_Z.w * 2.0,
+        };
+        let zero = Rotation {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 0.0,
+        };
+        assert_eq!(
+            Matrix4::from_rotation(&doubled),
+            Matrix4::from_rotation(&QUARTER_TURN_Z)
+        );
+        assert_eq!(Matrix4::from_rotation(&zero), Matrix4::IDENTITY);
+    }
+
+    #[test]
+    fn from_transform_is_row_major_with_translation_in_last_column() {
+        let transform = Matrix4::from_transform(
+            &Vec3::new(10.0, 20.0, 30.0),
+            &QUARTER_TURN_Z,
+            &Vec3::new(2.0, 3.0, 4.0),
+        );
+        assert_eq!(
+            [transform.m[0][3], transform.m[1][3], transform.m[2][3]],
+            [10.0, 20.0, 30.0]
+        );
+        assert_eq!(transform.m[3], [0.0, 0.0, 0.0, 1.0]);
+        // The scaled local X axis ends up in column 0.
+        assert!((transform.m[1][0] - 2.0).abs() < 1e-9);
+        assert!((transform.m[0][1] + 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn from_transform_scales_then_rotates_then_translates() {
+        let position = Vec3::new(10.0, 0.0, 0.0);
+        let scale = Vec3::new(2.0, 1.0, 1.0);
+        let transform = Matrix4::from_transform(&position, &QUARTER_TURN_Z, &scale);
+        let expected = Matrix4::from_transform(&position, &NO_ROTATION, &Vec3::ONE)
+            * Matrix4::from_rotation(&QUARTER_TURN_Z)
+            * Matrix4::from_transform(&Vec3::ZERO, &NO_ROTATION, &scale);
+        for row in 0..4 {
+            for column in 0..4 {
+                assert!((transform.m[row][column] - expected.m[row][column]).abs() < 1e-9);
+            }
+        }
+        assert_near(
+            transform.transform_point(&Vec3::new(1.0, 0.0, 0.0)),
+            Vec3::new(10.0, 2.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn transform_point_applies_translation_but_transform_vector_does_not() {
+        let transform = Matrix4::from_transform(
+            &Vec3::new(1.0, 2.0, 3.0),
+            &QUARTER_TURN_Z,
+            &Vec3::new(2.0, 2.0, 2.0),
+        );
+        let point = Vec3::new(1.0, 1.0, 1.0);
+        assert_near(transform.transform_point(&point), Vec3::new(-1.0, 4.0, 5.0));
+        assert_near(
+            transform.transform_vector(&point),
+            Vec3::new(-2.0, 2.0, 2.0),
+        );
+        assert_near(
+            Matrix4::IDENTITY.transform_point(&point),
+            Vec3::new(1.0, 1.0, 1.0),
+        );
+    }
+}
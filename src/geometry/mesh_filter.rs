@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+
+use rpkg_rs::resource::partition_manager::PartitionManager;
+use rpkg_rs::resource::runtime_resource_id::RuntimeResourceID;
+
+use crate::geometry::bounding_box::BoundingBox;
+use crate::geometry::oriented_bounding_box::OrientedBoundingBox;
+use crate::geometry::prim_bounds::PrimBounds;
+use crate::geometry::scene_bounds::SceneBounds;
+use crate::json_serde::entities_json::{EntitiesJson, MeshHashesAndEntity, PfBoxType, Vec3};
+use crate::logging::logger::Logger;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MeshFilterOptions {
+    // Each mesh is tested with the bounds stored in the header of its PRIM, placed with the
+    // entity's transform and grown by this distance on every side. Larger margins drop fewer
+    // meshes.
+    pub margin: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshFilterStatus {
+    Kept,
+    // The scene has include boxes and the mesh touches none of them.
+    OutsideIncludeBoxes,
+    // The mesh lies entirely inside an exclude box.
+    InsideExcludeBox,
+}
+
+pub struct MeshFilter;
+
+impl MeshFilter {
+    pub fn mesh_bounds(
+        mesh: &MeshHashesAndEntity,
+        prim_bounds: &BoundingBox,
+        options: &MeshFilterOptions,
+    ) -> OrientedBoundingBox {
+        let mut mesh_bounds = OrientedBoundingBox::from_bounding_box(
+            prim_bounds,
+            &SceneBounds::mesh_world_matrix(&mesh.entity),
+        );
+        mesh_bounds.half_extents =
+            mesh_bounds.half_extents + Vec3::new(options.margin, options.margin, options.margin);
+        mesh_bounds
+    }
+
+    // Reads the bounds of every PRIM the scene's meshes use.
+    pub fn read_prim_bounds(
+        entities_json: &EntitiesJson,
+        runtime_folder: &str,
+        partition_manager: &PartitionManager,
+        logger: &Logger,
+    ) -> HashMap<RuntimeResourceID, BoundingBox> {
+        PrimBounds::read(
+            runtime_folder,
+            entities_json.meshes.iter().map(|mesh| &mesh.prim_hash),
+            partition_manager,
+            logger,
+        )
+    }
+
+    // Returns one status per mesh, in scene order. Boxes of unknown type are ignored, and a scene
+    // without include boxes keeps every mesh that is not excluded. Meshes whose PRIM is missing
+    // from `prim_bounds` are kept, since they cannot be known to be outside.
+    pub fn classify(
+        entities_json: &EntitiesJson,
+        prim_bounds: &HashMap<RuntimeResourceID, BoundingBox>,
+        options: &MeshFilterOptions,
+    ) -> Vec<MeshFilterStatus> {
+        let boxes_of_type = |pf_box_type: &PfBoxType| {
+            entities_json
+                .pf_boxes
+                .iter()
+                .filter(|pf_box| pf_box.r#type.data == *pf_box_type)
+                .map(OrientedBoundingBox::from_pf_box)
+                .collect::<Vec<_>>()
+        };
+        let include_boxes = boxes_of_type(&PfBoxType::IncludeMeshCollision);
+        let exclude_boxes = boxes_of_type(&PfBoxType::ExcludeMeshCollision);
+        entities_json
+            .meshes
+            .iter()
+            .map(|mesh| {
+                let mesh_bounds = match prim_bounds.get(&mesh.prim_hash) {
+                    Some(prim_bounds) => Self::mesh_bounds(mesh, prim_bounds, options),
+                    None => return MeshFilterStatus::Kept,
+                };
+                if exclude_boxes
+                    .iter()
+                    .any(|exclude_box| exclude_box.contains(&mesh_bounds))
+                {
+                    MeshFilterStatus::InsideExcludeBox
+                } else if !include_boxes.is_empty()
+                    && !include_boxes
+                        .iter()
+                        .any(|include_box| include_box.intersects(&mesh_bounds))
+                {
+                    MeshFilterStatus::OutsideIncludeBoxes
+                } else {
+                    MeshFilterStatus::Kept
+                }
+            })
+            .collect()
+    }
+
+    pub fn filter<'a>(
+        entities_json: &'a EntitiesJson,
+        runtime_folder: &str,
+        partition_manager: &PartitionManager,
+        options: &MeshFilterOptions,
+        logger: &Logger,
+    ) -> Vec<&'a MeshHashesAndEntity> {
+        let logger = logger.with_module(module_path!());
+        let prim_bounds =
+            Self::read_prim_bounds(entities_json, runtime_folder, partition_manager, &logger);
+        let statuses = Self::classify(entities_json, &prim_bounds, options);
+        let count = |status: MeshFilterStatus| statuses.iter().filter(|s| **s == status).count();
+        let unbounded = entities_json
+            .meshes
+            .iter()
+            .filter(|mesh| !prim_bounds.contains_key(&mesh.prim_hash))
+            .count();
+        logger.info(format!(
+            "Kept {} of {} meshes: {} outside include boxes, {} inside exclude boxes.",
+            count(MeshFilterStatus::Kept),
+            statuses.len(),
+            count(MeshFilterStatus::OutsideIncludeBoxes),
+            count(MeshFilterStatus::InsideExcludeBox)
+        ));
+        if unbounded > 0 {
+            logger.warn(format!(
+                "Kept {} meshes whose PRIM bounds could not be read.",
+                unbounded
+            ));
+        }
+        entities_json
+            .meshes
+            .iter()
+            .zip(statuses)
+            .filter_map(|(mesh, status)| (status == MeshFilterStatus::Kept).then_some(mesh))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::test_support::{mesh, pf_box, scale, scene};
+
+    // Gives every mesh of the scene a PRIM of size 2 around its origin.
+    fn unit_prims(entities_json: &EntitiesJson) -> HashMap<RuntimeResourceID, BoundingBox> {
+        entities_json
+            .meshes
+            .iter()
+            .map(|mesh| {
+                (
+                    mesh.prim_hash,
+                    BoundingBox::from_center_and_half_extents(Vec3::ZERO, Vec3::ONE),
+                )
+            })
+            .collect()
+    }
+
+    fn classify(entities_json: &EntitiesJson) -> Vec<MeshFilterStatus> {
+        MeshFilter::classify(
+            entities_json,
+            &unit_prims(entities_json),
+            &MeshFilterOptions::default(),
+        )
+    }
+
+    #[test]
+    fn meshes_without_prim_bounds_are_kept() {
+        let entities_json = scene(
+            vec![mesh(Vec3::new(1000.0, 0.0, 0.0)), mesh(Vec3::ZERO)],
+            vec![
+                pf_box(
+                    PfBoxType::IncludeMeshCollision,
+                    Vec3::new(-1000.0, 0.0, 0.0),
+                    Vec3::ONE,
+                ),
+                pf_box(
+                    PfBoxType::ExcludeMeshCollision,
+                    Vec3::ZERO,
+                    Vec3::new(100.0, 100.0, 100.0),
+                ),
+            ],
+        );
+        assert_eq!(
+            MeshFilter::classify(
+                &entities_json,
+                &HashMap::new(),
+                &MeshFilterOptions::default()
+            ),
+            vec![MeshFilterStatus::Kept, MeshFilterStatus::Kept]
+        );
+    }
+
+    #[test]
+    fn include_boxes_drop_meshes_that_touch_none_of_them() {
+        let entities_json = scene(
+            vec![
+                mesh(Vec3::ZERO),
+                mesh(Vec3::new(20.0, 0.0, 0.0)),
+                mesh(Vec3::new(0.0, 0.0, 50.0)),
+            ],
+            vec![
+                pf_box(
+                    PfBoxType::IncludeMeshCollision,
+                    Vec3::ZERO,
+                    Vec3::new(10.0, 10.0, 10.0),
+                ),
+                pf_box(
+                    PfBoxType::IncludeMeshCollision,
+                    Vec3::new(20.0, 0.0, 0.0),
+                    Vec3::new(10.0, 10.0, 10.0),
+                ),
+            ],
+        );
+        assert_eq!(
+            classify(&entities_json),
+            vec![
+                MeshFilterStatus::Kept,
+                MeshFilterStatus::Kept,
+                MeshFilterStatus::OutsideIncludeBoxes,
+            ]
+        );
+    }
+
+    #[test]
+    fn exclude_boxes_only_drop_meshes_entirely_inside() {
+        let entities_json = scene(
+            vec![
+                mesh(Vec3::ZERO),
+                mesh(Vec3::new(5.0, 0.0, 0.0)),
+                mesh(Vec3::new(50.0, 0.0, 0.0)),
+            ],
+            vec![pf_box(
+                PfBoxType::ExcludeMeshCollision,
+                Vec3::ZERO,
+                Vec3::new(10.0, 10.0, 10.0),
+            )],
+        );
+        assert_eq!(
+            classify(&entities_json),
+            vec![
+                MeshFilterStatus::InsideExcludeBox,
+                MeshFilterStatus::Kept,
+                MeshFilterStatus::Kept,
+            ]
+        );
+    }
+
+    #[test]
+    fn boundary_contact_keeps_included_meshes_and_drops_excluded_ones() {
+        // Each mesh touches the face of a box of size 10 centred on the origin.
+        let include_scene = scene(
+            vec![
+                mesh(Vec3::new(6.0, 0.0, 0.0)),
+                mesh(Vec3::new(6.5, 0.0, 0.0)),
+            ],
+            vec![pf_box(
+                PfBoxType::IncludeMeshCollision,
+                Vec3::ZERO,
+                Vec3::new(10.0, 10.0, 10.0),
+            )],
+        );
+        assert_eq!(
+            classify(&include_scene),
+            vec![
+                MeshFilterStatus::Kept,
+                MeshFilterStatus::OutsideIncludeBoxes
+            ]
+        );
+        let exclude_scene = scene(
+            vec![
+                mesh(Vec3::new(4.0, 0.0, 0.0)),
+                mesh(Vec3::new(4.5, 0.0, 0.0)),
+            ],
+            vec![pf_box(
+                PfBoxType::ExcludeMeshCollision,
+                Vec3::ZERO,
+                Vec3::new(10.0, 10.0, 10.0),
+            )],
+        );
+        assert_eq!(
+            classify(&exclude_scene),
+            vec![MeshFilterStatus::InsideExcludeBox, MeshFilterStatus::Kept]
+        );
+    }
+
+    #[test]
+    fn unknown_boxes_are_ignored() {
+        let entities_json = scene(
+            vec![mesh(Vec3::ZERO)],
+            vec![
+                pf_box(
+                    PfBoxType::Unknown("PFBT_UNKNOWN".to_string()),
+                    Vec3::new(100.0, 0.0, 0.0),
+                    Vec3::ONE,
+                ),
+                pf_box(
+                    PfBoxType::Unknown("PFBT_UNKNOWN".to_string()),
+                    Vec3::ZERO,
+                    Vec3::new(10.0, 10.0, 10.0),
+                ),
+            ],
+        );
+        assert_eq!(classify(&entities_json), vec![MeshFilterStatus::Kept]);
+    }
+
+    #[test]
+    fn prim_bounds_follow_entity_scale() {
+        let mut scaled_mesh = mesh(Vec3::new(20.0, 0.0, 0.0));
+        scaled_mesh.entity.scale = scale(Vec3::new(20.0, 1.0, 1.0));
+        let entities_json = scene(
+            vec![scaled_mesh],
+            vec![pf_box(
+                PfBoxType::IncludeMeshCollision,
+                Vec3::ZERO,
+                Vec3::new(10.0, 10.0, 10.0),
+            )],
+        );
+        assert_eq!(classify(&entities_json), vec![MeshFilterStatus::Kept]);
+    }
+
+    #[test]
+    fn margin_grows_mesh_bounds() {
+        let entities_json = scene(
+            vec![mesh(Vec3::new(6.5, 0.0, 0.0))],
+            vec![pf_box(
+                PfBoxType::IncludeMeshCollision,
+                Vec3::ZERO,
+                Vec3::new(10.0, 10.0, 10.0),
+            )],
+        );
+        assert_eq!(
+            MeshFilter::classify(
+                &entities_json,
+                &unit_prims(&entities_json),
+                &MeshFilterOptions { margin: 0.5 }
+            ),
+            vec![MeshFilterStatus::Kept]
+        );
+    }
+
+    #[test]
+    fn prim_bounds_need_not_be_centred_on_the_origin() {
+        let entities_json = scene(
+            vec![mesh(Vec3::new(8.0, 0.0, 0.0))],
+            vec![pf_box(
+                PfBoxType::IncludeMeshCollision,
+                Vec3::ZERO,
+                Vec3::new(10.0, 10.0, 10.0),
+            )],
+        );
+        let prim_bounds = HashMap::from([(
+            entities_json.meshes[0].prim_hash,
+            BoundingBox::new(Vec3::new(-4.0, 0.0, 0.0), Vec3::new(-2.0, 1.0, 1.0)),
+        )]);
+        assert_eq!(
+            MeshFilter::classify(&entities_json, &prim_bounds, &MeshFilterOptions::default()),
+            vec![MeshFilterStatus::Kept]
+        );
+    }
+}
@@ -0,0 +1,9 @@
+pub mod bounding_box;
+pub mod matrix4;
+pub mod mesh_filter;
+pub mod oriented_bounding_box;
+pub mod prim_bounds;
+pub mod scene_bounds;
+#[cfg(test)]
+mod test_support;
+pub mod vec3_ops;
\ No newline at end of file
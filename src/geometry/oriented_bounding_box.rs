@@ -0,0 +1,226 @@
+use crate::geometry::bounding_box::BoundingBox;
+use crate::geometry::matrix4::Matrix4;
+use crate::json_serde::entities_json::{PfBox, Vec3};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientedBoundingBox {
+    pub center: Vec3,
+    // Unit axes of the box in world space.
+    pub axes: [Vec3; 3],
+    // Half the box size along each of `axes`.
+    pub half_extents: Vec3,
+}
+
+impl OrientedBoundingBox {
+    // A pathfinding box is centred on its position and its scale holds the full box size.
+    pub fn from_pf_box(pf_box: &PfBox) -> Self {
+        let rotation = Matrix4::from_rotation(&pf_box.rotation);
+        Self {
+            center: pf_box.position,
+            axes: [rotation.axis(0), rotation.axis(1), rotation.axis(2)],
+            half_extents: pf_box.scale.data.abs() * 0.5,
+        }
+    }
+
+    // The box after `transform`. Any scale in `transform` is moved into the half extents.
+    pub fn from_bounding_box(bounding_box: &BoundingBox, transform: &Matrix4) -> Self {
+        let half_extents = bounding_box.half_extents();
+        let mut axes = [Vec3::ZERO; 3];
+        let mut extents = [half_extents.x, half_extents.y, half_extents.z];
+        for (index, axis) in axes.iter_mut().enumerate() {
+            let scaled_axis = transform.axis(index);
+            let length = scaled_axis.length();
+            *axis = if length == 0.0 {
+                scaled_axis
+            } else {
+                scaled_axis * (1.0 / length)
+            };
+            extents[index] *= length;
+        }
+        Self {
+            center: transform.transform_point(&bounding_box.center()),
+            axes,
+            half_extents: Vec3::new(extents[0], extents[1], extents[2]),
+        }
+    }
+
+    fn half_extent(&self, index: usize) -> f64 {
+        [
+            self.half_extents.x,
+            self.half_extents.y,
+            self.half_extents.z,
+        ][index]
+    }
+
+    pub fn corners(&self) -> [Vec3; 8] {
+        let mut corners = [Vec3::ZERO; 8];
+        for (index, corner) in corners.iter_mut().enumerate() {
+            *corner = (0..3).fold(self.center, |corner, axis| {
+                let sign = if index & (1 << axis) == 0 { -1.0 } else { 1.0 };
+                corner + self.axes[axis] * (sign * self.half_extent(axis))
+            });
+        }
+        corners
+    }
+
+    pub fn contains_point(&self, point: &Vec3) -> bool {
+        let offset = *point - self.center;
+        (0..3).all(|axis| offset.dot(&self.axes[axis]).abs() <= self.half_extent(axis))
+    }
+
+    // True if every corner of `other` lies inside this box.
+    pub fn contains(&self, other: &OrientedBoundingBox) -> bool {
+        other
+            .corners()
+            .iter()
+            .all(|corner| self.contains_point(corner))
+    }
+
+    // Separating axis test over the face normals of both boxes and their pairwise cross products.
+    pub fn intersects(&self, other: &OrientedBoundingBox) -> bool {
+        let mut axes = Vec::with_capacity(15);
+        axes.extend_from_slice(&self.axes);
+        axes.extend_from_slice(&other.axes);
+        for a in &self.axes {
+            for b in &other.axes {
+                axes.push(Vec3::new(
+                    a.y * b.z - a.z * b.y,
+                    a.z * b.x - a.x * b.z,
+                    a.x * b.y - a.y * b.x,
+                ));
+            }
+        }
+        let offset = other.center - self.center;
+        axes.iter().filter(|axis| axis.length() > 1e-9).all(|axis| {
+            offset.dot(axis).abs() <= self.projected_radius(axis) + other.projected_radius(axis)
+        })
+    }
+
+    fn projected_radius(&self, axis: &Vec3) -> f64 {
+        (0..3)
+            .map(|index| self.half_extent(index) * self.axes[index].dot(axis).abs())
+            .sum()
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::from_points(&self.corners())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::test_support::{assert_near, pf_box, NO_ROTATION};
+    use crate::json_serde::entities_json::{PfBoxType, Rotation};
+
+    // 45 degrees about +Z.
+    fn eighth_turn_z() -> Rotation {
+        let angle = std::f64::consts::FRAC_PI_8;
+        Rotation {
+            x: 0.0,
+            y: 0.0,
+            z: angle.sin(),
+            w: angle.cos(),
+        }
+    }
+
+    fn oriented_box(center: Vec3, rotation: &Rotation, half_extents: Vec3) -> OrientedBoundingBox {
+        let rotation = Matrix4::from_rotation(rotation);
+        OrientedBoundingBox {
+            center,
+            axes: [rotation.axis(0), rotation.axis(1), rotation.axis(2)],
+            half_extents,
+        }
+    }
+
+    #[test]
+    fn from_pf_box_uses_scale_as_full_size() {
+        let pf_box = PfBox {
+            rotation: eighth_turn_z(),
+            ..pf_box(
+                PfBoxType::IncludeMeshCollision,
+                Vec3::new(1.0, 2.0, 3.0),
+                Vec3::new(4.0, -2.0, 6.0),
+            )
+        };
+        let oriented_bounding_box = OrientedBoundingBox::from_pf_box(&pf_box);
+        let half_sqrt_2 = std::f64::consts::FRAC_1_SQRT_2;
+        assert_eq!(oriented_bounding_box.center, Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(oriented_bounding_box.half_extents, Vec3::new(2.0, 1.0, 3.0));
+        assert_near(
+            oriented_bounding_box.axes[0],
+            Vec3::new(half_sqrt_2, half_sqrt_2, 0.0),
+        );
+        assert_near(
+            oriented_bounding_box.axes[1],
+            Vec3::new(-half_sqrt_2, half_sqrt_2, 0.0),
+        );
+        assert_near(oriented_bounding_box.axes[2], Vec3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn contains_requires_every_corner_inside() {
+        let outer = oriented_box(Vec3::ZERO, &eighth_turn_z(), Vec3::new(5.0, 5.0, 5.0));
+        let inside = oriented_box(Vec3::new(1.0, 1.0, 0.0), &NO_ROTATION, Vec3::ONE);
+        let poking_out = oriented_box(Vec3::new(6.0, 0.0, 0.0), &NO_ROTATION, Vec3::ONE);
+        assert!(outer.contains(&inside));
+        assert!(!outer.contains(&poking_out));
+        assert!(!inside.contains(&outer));
+    }
+
+    #[test]
+    fn intersects_overlapping_boxes() {
+        let first = oriented_box(Vec3::ZERO, &NO_ROTATION, Vec3::ONE);
+        let second = oriented_box(Vec3::new(1.5, 1.5, 0.0), &eighth_turn_z(), Vec3::ONE);
+        assert!(first.intersects(&second));
+        assert!(second.intersects(&first));
+    }
+
+    #[test]
+    fn intersects_finds_separating_face_axis() {
+        let first = oriented_box(Vec3::ZERO, &NO_ROTATION, Vec3::ONE);
+        let second = oriented_box(Vec3::new(2.5, 0.0, 0.0), &NO_ROTATION, Vec3::ONE);
+        assert!(!first.intersects(&second));
+    }
+
+    #[test]
+    fn intersects_finds_separating_axis_of_rotated_box() {
+        // The axis-aligned bounds of these boxes overlap, but the rotated box's own X axis
+        // separates them.
+        let first = oriented_box(Vec3::ZERO, &NO_ROTATION, Vec3::ONE);
+        let second = oriented_box(Vec3::new(2.3, 2.3, 0.0), &eighth_turn_z(), Vec3::ONE);
+        assert!(first.bounding_box().intersects(&second.bounding_box()));
+        assert!(!first.intersects(&second));
+        assert!(!second.intersects(&first));
+    }
+
+    #[test]
+    fn intersects_finds_separating_edge_cross_product_axis() {
+        // Two thin bars that cross each other at a distance. No face normal separates them, only
+        // the cross product of their long edges.
+        let half_sqrt_2 = std::f64::consts::FRAC_1_SQRT_2;
+        let bar = |center: Vec3, axes: [Vec3; 3]| OrientedBoundingBox {
+            center,
+            axes,
+            half_extents: Vec3::new(5.0, 0.1, 0.1),
+        };
+        let first_axes = [
+            Vec3::new(half_sqrt_2, 0.0, half_sqrt_2),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(-half_sqrt_2, 0.0, half_sqrt_2),
+        ];
+        let second_axes = [
+            Vec3::new(0.0, half_sqrt_2, half_sqrt_2),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, half_sqrt_2, -half_sqrt_2),
+        ];
+        let edge_normal = Vec3::new(-1.0, -1.0, 1.0) * (1.0 / 3.0_f64.sqrt());
+        let first = bar(Vec3::ZERO, first_axes);
+        let apart = bar(edge_normal * 0.6, second_axes);
+        let touching = bar(edge_normal * 0.2, second_axes);
+        assert!(!first.intersects(&apart));
+        assert!(!apart.intersects(&first));
+        assert!(first.intersects(&touching));
+    }
+}
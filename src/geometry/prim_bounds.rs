@@ -0,0 +1,197 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+use rpkg_rs::resource::partition_manager::PartitionManager;
+use rpkg_rs::resource::resource_package::ResourcePackage;
+use rpkg_rs::resource::runtime_resource_id::RuntimeResourceID;
+
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::geometry::bounding_box::BoundingBox;
+use crate::json_serde::entities_json::Vec3;
+use crate::logging::logger::Logger;
+use crate::package::package_scan::PackageScan;
+use crate::package::resource_hash::ResourceHash;
+
+// Local bounding boxes of PRIM (render primitive) resources.
+//
+// A PRIM resource starts with the offset of its object header, which is laid out as draw
+// destination (u8), pack type (u8), primitive type (u16), property flags, bone rig resource
+// index, object count and object table offset (u32 each), followed by the minimum and maximum
+// corners of the primitive's bounds as three little-endian f32 each.
+pub struct PrimBounds;
+
+impl PrimBounds {
+    // The primitive type of object headers.
+    const OBJECT_HEADER_TYPE: u16 = 1;
+    const TYPE_OFFSET: usize = 2;
+    const BOUNDS_OFFSET: usize = 20;
+    const HEADER_SIZE: usize = Self::BOUNDS_OFFSET + 6 * 4;
+
+    pub fn parse(prim: &[u8]) -> Result<BoundingBox, NavKitError> {
+        let invalid = |message: String| NavKitError::new(NavKitErrorCode::InvalidPrim, message);
+        let header_offset = prim
+            .get(..4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+            .ok_or_else(|| invalid("PRIM is too small to hold a header offset".to_string()))?;
+        let header = header_offset
+            .checked_add(Self::HEADER_SIZE)
+            .and_then(|header_end| prim.get(header_offset..header_end))
+            .ok_or_else(|| {
+                invalid(format!(
+                    "PRIM header at offset {} lies past the end of the {} byte resource",
+                    header_offset,
+                    prim.len()
+                ))
+            })?;
+        let primitive_type =
+            u16::from_le_bytes([header[Self::TYPE_OFFSET], header[Self::TYPE_OFFSET + 1]]);
+        if primitive_type != Self::OBJECT_HEADER_TYPE {
+            return Err(invalid(format!(
+                "PRIM header has primitive type {}, not an object header",
+                primitive_type
+            )));
+        }
+        let coordinate = |index: usize| {
+            let start = Self::BOUNDS_OFFSET + index * 4;
+            f32::from_le_bytes(header[start..start + 4].try_into().unwrap()) as f64
+        };
+        let bounds = BoundingBox::new(
+            Vec3::new(coordinate(0), coordinate(1), coordinate(2)),
+            Vec3::new(coordinate(3), coordinate(4), coordinate(5)),
+        );
+        let finite = [bounds.min, bounds.max]
+            .iter()
+            .all(|corner| corner.x.is_finite() && corner.y.is_finite() && corner.z.is_finite());
+        if !finite || bounds.is_empty() {
+            return Err(invalid(format!(
+                "PRIM header has invalid bounds {:?} to {:?}",
+                bounds.min, bounds.max
+            )));
+        }
+        Ok(bounds)
+    }
+
+    // Reads the bounds of each distinct PRIM once, opening each package once. PRIMs whose bounds
+    // cannot be read are logged and left out of the result.
+    pub fn read<'a>(
+        runtime_folder: &str,
+        prim_hashes: impl IntoIterator<Item = &'a RuntimeResourceID>,
+        partition_manager: &PartitionManager,
+        logger: &Logger,
+    ) -> HashMap<RuntimeResourceID, BoundingBox> {
+        let logger = logger.with_module(module_path!());
+        let warn = |rrid: &RuntimeResourceID, error: &NavKitError| {
+            logger.warn(format!(
+                "Could not read the bounds of PRIM {}, keeping the meshes that use it: {}",
+                ResourceHash::describe(rrid),
+                error.message
+            ));
+        };
+        let mut prims_by_package: BTreeMap<String, Vec<RuntimeResourceID>> = BTreeMap::new();
+        for rrid in prim_hashes.into_iter().collect::<HashSet<_>>() {
+            let resource_info = match PackageScan::get_resource_info(partition_manager, rrid) {
+                Some(info) => info,
+                None => {
+                    warn(
+                        rrid,
+                        &NavKitError::new(
+                            NavKitErrorCode::UnknownHash,
+                            "Error getting resource info",
+                        ),
+                    );
+                    continue;
+                }
+            };
+            let data_type = resource_info.last_occurrence.data_type();
+            if data_type != "PRIM" {
+                warn(
+                    rrid,
+                    &NavKitError::new(
+                        NavKitErrorCode::InvalidResourceType,
+                        format!("Resource is a {} resource, not PRIM", data_type),
+                    ),
+                );
+                continue;
+            }
+            prims_by_package
+                .entry(resource_info.last_partition)
+                .or_default()
+                .push(*rrid);
+        }
+
+        let mut bounds = HashMap::new();
+        for (package_name, rrids) in prims_by_package {
+            let package_path = PathBuf::from(runtime_folder).join(&package_name);
+            let rpkg = ResourcePackage::from_file(&package_path).map_err(|e| {
+                NavKitError::new(
+                    NavKitErrorCode::PackageOpen,
+                    format!("Failed parse resource package {}: {}", package_name, e),
+                )
+            });
+            for rrid in rrids {
+                let prim_bounds = rpkg.as_ref().map_err(Clone::clone).and_then(|rpkg| {
+                    let prim = rpkg.read_resource(&package_path, &rrid).map_err(|e| {
+                        NavKitError::new(
+                            NavKitErrorCode::ResourceRead,
+                            format!("Failed extract resource: {}", e),
+                        )
+                    })?;
+                    Self::parse(&prim)
+                });
+                match prim_bounds {
+                    Ok(prim_bounds) => {
+                        bounds.insert(rrid, prim_bounds);
+                    }
+                    Err(e) => warn(&rrid, &e),
+                }
+            }
+        }
+        bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An object header at `header_offset` with the given primitive type and bounds.
+    fn prim(header_offset: u32, primitive_type: u16, min: [f32; 3], max: [f32; 3]) -> Vec<u8> {
+        let mut prim = vec![0u8; header_offset as usize];
+        prim[..4].copy_from_slice(&header_offset.to_le_bytes());
+        prim.extend_from_slice(&[0, 0]);
+        prim.extend_from_slice(&primitive_type.to_le_bytes());
+        prim.extend_from_slice(&[0u8; 16]);
+        for coordinate in min.iter().chain(max.iter()) {
+            prim.extend_from_slice(&coordinate.to_le_bytes());
+        }
+        prim
+    }
+
+    #[test]
+    fn parse_reads_the_object_header_bounds() {
+        let bounds = PrimBounds::parse(&prim(16, 1, [-1.0, -2.0, 0.0], [1.0, 2.0, 3.5])).unwrap();
+        assert_eq!(bounds.min, Vec3::new(-1.0, -2.0, 0.0));
+        assert_eq!(bounds.max, Vec3::new(1.0, 2.0, 3.5));
+    }
+
+    #[test]
+    fn parse_rejects_truncated_or_invalid_headers() {
+        let valid = prim(16, 1, [-1.0, -1.0, -1.0], [1.0, 1.0, 1.0]);
+        let mut offset_past_end = valid.clone();
+        offset_past_end[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let invalid_prims = [
+            Vec::new(),
+            valid[..valid.len() - 1].to_vec(),
+            offset_past_end,
+            prim(16, 2, [-1.0, -1.0, -1.0], [1.0, 1.0, 1.0]),
+            prim(16, 1, [1.0, 1.0, 1.0], [-1.0, -1.0, -1.0]),
+            prim(16, 1, [f32::NAN, 0.0, 0.0], [1.0, 1.0, 1.0]),
+        ];
+        for invalid_prim in invalid_prims {
+            assert_eq!(
+                PrimBounds::parse(&invalid_prim).unwrap_err().code,
+                NavKitErrorCode::InvalidPrim
+            );
+        }
+    }
+}
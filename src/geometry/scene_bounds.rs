@@ -0,0 +1,92 @@
+use crate::geometry::bounding_box::BoundingBox;
+use crate::geometry::matrix4::Matrix4;
+use crate::geometry::oriented_bounding_box::OrientedBoundingBox;
+use crate::json_serde::entities_json::{Aloc, EntitiesJson, PfBox, PfSeedPoint, Vec3};
+
+pub struct SceneBounds;
+
+impl SceneBounds {
+    pub fn mesh_world_matrix(entity: &Aloc) -> Matrix4 {
+        Matrix4::from_transform(&entity.position, &entity.rotation, &entity.scale.data)
+    }
+
+    // Maps the unit cube centred on the origin onto the pathfinding box.
+    pub fn pf_box_world_matrix(pf_box: &PfBox) -> Matrix4 {
+        Matrix4::from_transform(&pf_box.position, &pf_box.rotation, &pf_box.scale.data)
+    }
+
+    pub fn pf_seed_point_world_matrix(pf_seed_point: &PfSeedPoint) -> Matrix4 {
+        Matrix4::from_transform(&pf_seed_point.position, &pf_seed_point.rotation, &Vec3::ONE)
+    }
+
+    // Bounds of every mesh origin, seed point and pathfinding box corner. The scene file does not
+    // carry mesh geometry, so meshes only contribute their positions.
+    pub fn compute(entities_json: &EntitiesJson) -> BoundingBox {
+        let mesh_positions = entities_json.meshes.iter().map(|mesh| mesh.entity.position);
+        let pf_seed_point_positions = entities_json
+            .pf_seed_points
+            .iter()
+            .map(|pf_seed_point| pf_seed_point.position);
+        let pf_box_bounds = entities_json
+            .pf_boxes
+            .iter()
+            .map(|pf_box| OrientedBoundingBox::from_pf_box(pf_box).bounding_box())
+            .fold(BoundingBox::EMPTY, |bounds, pf_box_bounds| {
+                bounds.union(&pf_box_bounds)
+            });
+        mesh_positions
+            .chain(pf_seed_point_positions)
+            .fold(pf_box_bounds, |bounds, position| bounds.extended(&position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::test_support::{mesh, pf_box, scene, NO_ROTATION};
+    use crate::json_serde::entities_json::PfBoxType;
+
+    fn pf_seed_point(position: Vec3) -> PfSeedPoint {
+        PfSeedPoint {
+            id: 3,
+            position,
+            rotation: NO_ROTATION,
+        }
+    }
+
+    #[test]
+    fn compute_of_empty_scene_is_empty() {
+        assert!(SceneBounds::compute(&scene(Vec::new(), Vec::new())).is_empty());
+    }
+
+    #[test]
+    fn compute_covers_mesh_origins_seed_points_and_pf_box_corners() {
+        let mut entities_json = scene(
+            vec![mesh(Vec3::new(10.0, 0.0, 0.0))],
+            vec![pf_box(
+                PfBoxType::IncludeMeshCollision,
+                Vec3::new(0.0, 0.0, 1.0),
+                Vec3::new(2.0, 4.0, 6.0),
+            )],
+        );
+        entities_json.pf_seed_points = vec![pf_seed_point(Vec3::new(-5.0, 0.5, 0.0))];
+        let bounds = SceneBounds::compute(&entities_json);
+        assert_eq!(bounds.min, Vec3::new(-5.0, -2.0, -2.0));
+        assert_eq!(bounds.max, Vec3::new(10.0, 2.0, 4.0));
+    }
+
+    #[test]
+    fn pf_box_world_matrix_maps_unit_cube_onto_the_box() {
+        let pf_box = pf_box(
+            PfBoxType::IncludeMeshCollision,
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::new(2.0, 4.0, 6.0),
+        );
+        let unit_cube =
+            BoundingBox::from_center_and_half_extents(Vec3::ZERO, Vec3::new(0.5, 0.5, 0.5));
+        assert_eq!(
+            unit_cube.transformed(&SceneBounds::pf_box_world_matrix(&pf_box)),
+            OrientedBoundingBox::from_pf_box(&pf_box).bounding_box()
+        );
+    }
+}
@@ -0,0 +1,66 @@
+// Helpers and scene builders shared by the geometry tests.
+use crate::json_serde::entities_json::{
+    Aloc, EntitiesJson, MeshHashesAndEntity, PfBox, PfBoxType, Rotation, Scale, ScaleType, Type,
+    Vec3,
+};
+use rpkg_rs::resource::runtime_resource_id::RuntimeResourceID;
+
+pub(crate) const NO_ROTATION: Rotation = Rotation {
+    x: 0.0,
+    y: 0.0,
+    z: 0.0,
+    w: 1.0,
+};
+
+pub(crate) fn assert_near(actual: Vec3, expected: Vec3) {
+    assert!(
+        (actual - expected).length() < 1e-9,
+        "expected {:?}, got {:?}",
+        expected,
+        actual
+    );
+}
+
+pub(crate) fn scale(data: Vec3) -> Scale {
+    Scale {
+        r#type: ScaleType::SVector3,
+        data,
+    }
+}
+
+pub(crate) fn mesh(position: Vec3) -> MeshHashesAndEntity {
+    MeshHashesAndEntity {
+        aloc_hash: RuntimeResourceID::from(0x00123456789ABCDE),
+        prim_hash: RuntimeResourceID::from(0x00123456789ABCDF),
+        entity: Aloc {
+            id: 1,
+            name: None,
+            tblu: None,
+            position,
+            rotation: NO_ROTATION,
+            scale: scale(Vec3::ONE),
+        },
+    }
+}
+
+// An unrotated box of the given full size.
+pub(crate) fn pf_box(pf_box_type: PfBoxType, position: Vec3, size: Vec3) -> PfBox {
+    PfBox {
+        id: 2,
+        position,
+        rotation: NO_ROTATION,
+        r#type: Type {
+            r#type: "EPathFinderBoxType".to_string(),
+            data: pf_box_type,
+        },
+        scale: scale(size),
+    }
+}
+
+pub(crate) fn scene(meshes: Vec<MeshHashesAndEntity>, pf_boxes: Vec<PfBox>) -> EntitiesJson {
+    EntitiesJson {
+        meshes,
+        pf_boxes,
+        pf_seed_points: Vec::new(),
+    }
+}
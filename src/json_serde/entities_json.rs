@@ -1,133 +1,392 @@
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::os::raw::c_char;
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct EntitiesJson {
-    pub meshes: Vec<MeshHashesAndEntity>,
-    #[serde(rename = "pfBoxes")]
-    pub pf_boxes: Vec<PfBox>,
-    #[serde(rename = "pfSeedPoints")]
-    pub pf_seed_points: Vec<PfSeedPoint>,
-}
-
-impl EntitiesJson {
-    pub fn build_from_nav_json_file(
-        nav_json_file: String,
-        log_callback: extern "C" fn(*const c_char),
-    ) -> Option<EntitiesJson> {
-        let msg = std::ffi::CString::new(format!(
-            "Loading scene from nav.json file: {}",
-            nav_json_file
-        ))
-        .unwrap();
-        log_callback(msg.as_ptr());
-
-        let nav_json_string = match fs::read_to_string(nav_json_file.as_str()) {
-            Ok(c) => c,
-            Err(e) => {
-                let msg =
-                    std::ffi::CString::new(format!("Error reading nav.json file: {}", e)).unwrap();
-                log_callback(msg.as_ptr());
-                return None;
-            }
-        };
-        match EntitiesJson::build_from_nav_json_string(nav_json_string, log_callback) {
-            Some(json) => Some(json),
-            None => None,
-        }
-    }
-
-    pub fn build_from_nav_json_string(
-        nav_json_string: String,
-        log_callback: extern "C" fn(*const c_char),
-    ) -> Option<EntitiesJson> {
-        match serde_json::from_str(&nav_json_string) {
-            Ok(json) => Some(json),
-            Err(e) => {
-                let msg =
-                    std::ffi::CString::new(format!("Error parsing nav.json file: {}", e)).unwrap();
-                log_callback(msg.as_ptr());
-                None
-            }
-        }
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct BrickMessage {
-    pub brick_hash: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct MeshHashesAndEntity {
-    pub aloc_hash: String,
-    pub prim_hash: String,
-    pub entity: Aloc,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Aloc {
-    pub id: String,
-    pub name: Option<String>,
-    pub tblu: Option<String>,
-    pub position: Vec3,
-    pub rotation: Rotation,
-    pub scale: Scale,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct PfBox {
-    pub id: String,
-    pub position: Vec3,
-    pub rotation: Rotation,
-    #[serde(rename = "type")]
-    pub r#type: Type,
-    pub scale: Scale,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct PfSeedPoint {
-    pub id: String,
-    pub position: Vec3,
-    pub rotation: Rotation,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde()]
-pub struct Vec3 {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Rotation {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
-    pub w: f64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Scale {
-    #[serde(rename = "type")]
-    pub r#type: String,
-    pub data: Vec3,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Type {
-    #[serde(rename = "type")]
-    pub r#type: String,
-    pub data: String,
-}
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::logging::logger::Logger;
+use crate::package::resource_hash::ResourceHash;
+use rpkg_rs::resource::runtime_resource_id::RuntimeResourceID;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fs;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntitiesJson {
+    pub meshes: Vec<MeshHashesAndEntity>,
+    #[serde(rename = "pfBoxes")]
+    pub pf_boxes: Vec<PfBox>,
+    #[serde(rename = "pfSeedPoints")]
+    pub pf_seed_points: Vec<PfSeedPoint>,
+}
+
+impl EntitiesJson {
+    pub fn build_from_nav_json_file(
+        nav_json_file: String,
+        logger: &Logger,
+    ) -> Result<EntitiesJson, NavKitError> {
+        let logger = logger.with_module(module_path!());
+        logger.info(format!(
+            "Loading scene from nav.json file: {}",
+            nav_json_file
+        ));
+
+        let nav_json_string = match fs::read_to_string(nav_json_file.as_str()) {
+            Ok(c) => c,
+            Err(e) => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::NavJsonRead,
+                    format!("Error reading nav.json file: {}", e),
+                )
+                .log(&logger));
+            }
+        };
+        EntitiesJson::build_from_nav_json_string(nav_json_string, &logger)
+    }
+
+    pub fn build_from_nav_json_string(
+        nav_json_string: String,
+        logger: &Logger,
+    ) -> Result<EntitiesJson, NavKitError> {
+        let logger = logger.with_module(module_path!());
+        match serde_json::from_str(&nav_json_string) {
+            Ok(json) => Ok(json),
+            Err(e) => Err(NavKitError::new(
+                NavKitErrorCode::NavJsonParse,
+                format!("Error parsing nav.json file: {}", e),
+            )
+            .log(&logger)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrickMessage {
+    pub brick_hash: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeshHashesAndEntity {
+    #[serde(
+        serialize_with = "serialize_resource_id",
+        deserialize_with = "deserialize_resource_id"
+    )]
+    pub aloc_hash: RuntimeResourceID,
+    #[serde(
+        serialize_with = "serialize_resource_id",
+        deserialize_with = "deserialize_resource_id"
+    )]
+    pub prim_hash: RuntimeResourceID,
+    pub entity: Aloc,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Aloc {
+    #[serde(
+        serialize_with = "serialize_entity_id",
+        deserialize_with = "deserialize_entity_id"
+    )]
+    pub id: u64,
+    pub name: Option<String>,
+    pub tblu: Option<String>,
+    pub position: Vec3,
+    pub rotation: Rotation,
+    pub scale: Scale,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PfBox {
+    #[serde(
+        serialize_with = "serialize_entity_id",
+        deserialize_with = "deserialize_entity_id"
+    )]
+    pub id: u64,
+    pub position: Vec3,
+    pub rotation: Rotation,
+    #[serde(rename = "type")]
+    pub r#type: Type,
+    pub scale: Scale,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PfSeedPoint {
+    #[serde(
+        serialize_with = "serialize_entity_id",
+        deserialize_with = "deserialize_entity_id"
+    )]
+    pub id: u64,
+    pub position: Vec3,
+    pub rotation: Rotation,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde()]
+pub struct Vec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rotation {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scale {
+    #[serde(rename = "type")]
+    pub r#type: ScaleType,
+    pub data: Vec3,
+}
+
+// Unknown scale types keep their name, so they can be reported and written back unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScaleType {
+    SVector3,
+    Unknown(String),
+}
+
+impl ScaleType {
+    pub fn name(&self) -> &str {
+        match self {
+            ScaleType::SVector3 => "SVector3",
+            ScaleType::Unknown(name) => name,
+        }
+    }
+
+    pub fn kind(&self) -> ScaleKind {
+        match self {
+            ScaleType::SVector3 => ScaleKind::SVector3,
+            ScaleType::Unknown(_) => ScaleKind::Unknown,
+        }
+    }
+}
+
+impl Serialize for ScaleType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for ScaleType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(match name.as_str() {
+            "SVector3" => ScaleType::SVector3,
+            _ => ScaleType::Unknown(name),
+        })
+    }
+}
+
+// The C view of `ScaleType`, without the name of unknown types.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleKind {
+    SVector3,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Type {
+    #[serde(rename = "type")]
+    pub r#type: String,
+    pub data: PfBoxType,
+}
+
+// Like `ScaleType`, unknown box types keep their name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PfBoxType {
+    IncludeMeshCollision,
+    ExcludeMeshCollision,
+    Unknown(String),
+}
+
+impl PfBoxType {
+    pub fn name(&self) -> &str {
+        match self {
+            PfBoxType::IncludeMeshCollision => "PFBT_INCLUDE_MESH_COLLISION",
+            PfBoxType::ExcludeMeshCollision => "PFBT_EXCLUDE_MESH_COLLISION",
+            PfBoxType::Unknown(name) => name,
+        }
+    }
+
+    pub fn kind(&self) -> PfBoxKind {
+        match self {
+            PfBoxType::IncludeMeshCollision => PfBoxKind::IncludeMeshCollision,
+            PfBoxType::ExcludeMeshCollision => PfBoxKind::ExcludeMeshCollision,
+            PfBoxType::Unknown(_) => PfBoxKind::Unknown,
+        }
+    }
+}
+
+impl Serialize for PfBoxType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for PfBoxType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(match name.as_str() {
+            "PFBT_INCLUDE_MESH_COLLISION" => PfBoxType::IncludeMeshCollision,
+            "PFBT_EXCLUDE_MESH_COLLISION" => PfBoxType::ExcludeMeshCollision,
+            _ => PfBoxType::Unknown(name),
+        })
+    }
+}
+
+// The C view of `PfBoxType`, without the name of unknown types.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PfBoxKind {
+    IncludeMeshCollision,
+    ExcludeMeshCollision,
+    Unknown,
+}
+
+// Hashes are written as 16 upper-case hex digits and read in any form `ResourceHash::parse`
+// accepts.
+fn serialize_resource_id<S: Serializer>(
+    rrid: &RuntimeResourceID,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&rrid.to_hex_string())
+}
+
+pub(crate) fn deserialize_resource_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<RuntimeResourceID, D::Error> {
+    let hash = String::deserialize(deserializer)?;
+    if hash.trim().is_empty() {
+        return Err(D::Error::custom("hash is empty"));
+    }
+    ResourceHash::parse(&hash).map_err(|_| D::Error::custom(format!("malformed hash '{}'", hash)))
+}
+
+// Entity ids are written as 16 lower-case hex digits, as the game-side exporter does. When
+// reading, only that form and `0x` prefixed hex strings are accepted, so an id is never
+// ambiguous between hex and decimal.
+fn serialize_entity_id<S: Serializer>(id: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{:016x}", id))
+}
+
+pub(crate) fn deserialize_entity_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u64, D::Error> {
+    let id = String::deserialize(deserializer)?;
+    let hex_id = match id.strip_prefix("0x").or_else(|| id.strip_prefix("0X")) {
+        Some(hex_id) => hex_id,
+        None if id.len() == 16 => id.as_str(),
+        None => {
+            return Err(D::Error::custom(format!(
+                "malformed entity id '{}': expected 16 hex digits or a 0x prefixed hex number",
+                id
+            )));
+        }
+    };
+    if hex_id.is_empty() || !hex_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(D::Error::custom(format!("malformed entity id '{}'", id)));
+    }
+    u64::from_str_radix(hex_id, 16)
+        .map_err(|_| D::Error::custom(format!("malformed entity id '{}'", id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn entity_id(value: Value) -> Result<u64, String> {
+        deserialize_entity_id(value).map_err(|e| e.to_string())
+    }
+
+    fn resource_id(value: Value) -> Result<u64, String> {
+        deserialize_resource_id(value)
+            .map(|rrid| ResourceHash::to_u64(&rrid))
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn entity_id_accepts_16_hex_digits_and_0x_prefixed_hex() {
+        assert_eq!(entity_id(json!("fedcba9876543210")), Ok(0xFEDCBA9876543210));
+        assert_eq!(entity_id(json!("FEDCBA9876543210")), Ok(0xFEDCBA9876543210));
+        assert_eq!(entity_id(json!("0x1f")), Ok(0x1F));
+        assert_eq!(entity_id(json!("0X1F")), Ok(0x1F));
+    }
+
+    #[test]
+    fn entity_id_rejects_wrong_lengths_and_non_hex_input() {
+        for id in ["12", "000000000000000012", "", "0x", "0x12345678901234567"] {
+            assert!(entity_id(json!(id)).is_err(), "{}", id);
+        }
+        for id in ["fedcba987654321g", "0xzz", "0x-1", "fedcba98 7654321"] {
+            assert_eq!(
+                entity_id(json!(id)),
+                Err(format!("malformed entity id '{}'", id))
+            );
+        }
+        assert_eq!(
+            entity_id(json!("12")),
+            Err(
+                "malformed entity id '12': expected 16 hex digits or a 0x prefixed hex number"
+                    .to_string()
+            )
+        );
+        assert!(entity_id(json!(12)).is_err());
+    }
+
+    #[test]
+    fn resource_id_accepts_hex_hashes_and_resource_paths() {
+        assert_eq!(
+            resource_id(json!("00290D5B143172A3")),
+            Ok(0x00290D5B143172A3)
+        );
+        assert_eq!(
+            resource_id(json!("0x00290D5B143172A3")),
+            Ok(0x00290D5B143172A3)
+        );
+        assert_eq!(
+            resource_id(json!("[assembly:/_test/lib.a?/test_image.png].pc_webp")),
+            Ok(0x00290D5B143172A3)
+        );
+    }
+
+    #[test]
+    fn resource_id_rejects_empty_overlong_and_non_hex_hashes() {
+        assert_eq!(resource_id(json!("")), Err("hash is empty".to_string()));
+        assert_eq!(resource_id(json!("  ")), Err("hash is empty".to_string()));
+        for hash in ["zz", "00290D5B143172AZ", "0x", "00290D5B143172A3FF"] {
+            assert_eq!(
+                resource_id(json!(hash)),
+                Err(format!("malformed hash '{}'", hash))
+            );
+        }
+        assert!(resource_id(json!(12)).is_err());
+    }
+
+    #[test]
+    fn pf_box_type_keeps_the_name_of_unknown_types() {
+        let known: PfBoxType =
+            serde_json::from_value(json!("PFBT_EXCLUDE_MESH_COLLISION")).unwrap();
+        assert_eq!(known, PfBoxType::ExcludeMeshCollision);
+        assert_eq!(known.kind(), PfBoxKind::ExcludeMeshCollision);
+
+        let unknown: PfBoxType = serde_json::from_value(json!("PFBT_NEW_TYPE")).unwrap();
+        assert_eq!(unknown, PfBoxType::Unknown("PFBT_NEW_TYPE".to_string()));
+        assert_eq!(unknown.kind(), PfBoxKind::Unknown);
+        assert_eq!(
+            serde_json::to_value(&unknown).unwrap(),
+            json!("PFBT_NEW_TYPE")
+        );
+    }
+}
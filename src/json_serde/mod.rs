@@ -1 +1,3 @@
-pub mod entities_json;
\ No newline at end of file
+pub mod entities_json;
+pub mod scene_diff;
+pub mod scene_validation;
\ No newline at end of file
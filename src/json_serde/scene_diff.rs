@@ -0,0 +1,252 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use rpkg_rs::resource::runtime_resource_id::RuntimeResourceID;
+
+use crate::json_serde::entities_json::{EntitiesJson, MeshHashesAndEntity, Rotation, Vec3};
+use crate::logging::logger::Logger;
+use crate::package::resource_hash::ResourceHash;
+
+// Largest difference in any position, rotation or scale component still treated as unchanged.
+const TRANSFORM_TOLERANCE: f64 = 1e-6;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SceneEntityKind {
+    Mesh,
+    PfBox,
+    PfSeedPoint,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneChangeKind {
+    Added,
+    Removed,
+    // Position, rotation or scale changed.
+    Transformed,
+    // The transform is unchanged, but the mesh resources or the pathfinding box type changed.
+    Modified,
+    // Both the transform and the mesh resources or pathfinding box type changed.
+    TransformedAndModified,
+}
+
+#[derive(Debug, Clone)]
+pub struct SceneChange {
+    pub entity_kind: SceneEntityKind,
+    pub change_kind: SceneChangeKind,
+    pub id: u64,
+    // Index in the old scene's array, `None` for added entities.
+    pub old_index: Option<usize>,
+    // Index in the new scene's array, `None` for removed entities.
+    pub new_index: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SceneDiff {
+    // Ordered by entity kind, then id.
+    pub changes: Vec<SceneChange>,
+    // Hashes used by meshes in the new scene but by no mesh in the old one.
+    pub new_aloc_hashes: Vec<RuntimeResourceID>,
+    pub new_prim_hashes: Vec<RuntimeResourceID>,
+}
+
+struct EntityState<'a> {
+    position: &'a Vec3,
+    rotation: &'a Rotation,
+    scale: Option<&'a Vec3>,
+    // Everything other than the transform that is compared, such as the mesh hashes.
+    contents: String,
+}
+
+impl SceneDiff {
+    // Entities are matched by id. When an id appears more than once in a scene, only its first
+    // occurrence is compared.
+    pub fn compare(old: &EntitiesJson, new: &EntitiesJson, logger: &Logger) -> SceneDiff {
+        let logger = logger.with_module(module_path!());
+        let mut changes = Vec::new();
+        Self::compare_entities(
+            SceneEntityKind::Mesh,
+            Self::mesh_states(old),
+            Self::mesh_states(new),
+            &mut changes,
+        );
+        Self::compare_entities(
+            SceneEntityKind::PfBox,
+            Self::pf_box_states(old),
+            Self::pf_box_states(new),
+            &mut changes,
+        );
+        Self::compare_entities(
+            SceneEntityKind::PfSeedPoint,
+            Self::pf_seed_point_states(old),
+            Self::pf_seed_point_states(new),
+            &mut changes,
+        );
+
+        let scene_diff = SceneDiff {
+            changes,
+            new_aloc_hashes: Self::new_hashes(old, new, |mesh| mesh.aloc_hash),
+            new_prim_hashes: Self::new_hashes(old, new, |mesh| mesh.prim_hash),
+        };
+        logger.info(format!(
+            "Scene diff: {} changed entities, {} new ALOCs, {} new PRIMs.",
+            scene_diff.changes.len(),
+            scene_diff.new_aloc_hashes.len(),
+            scene_diff.new_prim_hashes.len()
+        ));
+        scene_diff
+    }
+
+    pub fn count(&self, entity_kind: SceneEntityKind, change_kind: SceneChangeKind) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.entity_kind == entity_kind && change.change_kind == change_kind)
+            .count()
+    }
+
+    fn mesh_states(entities_json: &EntitiesJson) -> Vec<(u64, EntityState<'_>)> {
+        entities_json
+            .meshes
+            .iter()
+            .map(|mesh| {
+                (
+                    mesh.entity.id,
+                    EntityState {
+                        position: &mesh.entity.position,
+                        rotation: &mesh.entity.rotation,
+                        scale: Some(&mesh.entity.scale.data),
+                        contents: format!(
+                            "{}/{}",
+                            mesh.aloc_hash.to_hex_string(),
+                            mesh.prim_hash.to_hex_string()
+                        ),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn pf_box_states(entities_json: &EntitiesJson) -> Vec<(u64, EntityState<'_>)> {
+        entities_json
+            .pf_boxes
+            .iter()
+            .map(|pf_box| {
+                (
+                    pf_box.id,
+                    EntityState {
+                        position: &pf_box.position,
+                        rotation: &pf_box.rotation,
+                        scale: Some(&pf_box.scale.data),
+                        contents: pf_box.r#type.data.name().to_string(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn pf_seed_point_states(entities_json: &EntitiesJson) -> Vec<(u64, EntityState<'_>)> {
+        entities_json
+            .pf_seed_points
+            .iter()
+            .map(|pf_seed_point| {
+                (
+                    pf_seed_point.id,
+                    EntityState {
+                        position: &pf_seed_point.position,
+                        rotation: &pf_seed_point.rotation,
+                        scale: None,
+                        contents: String::new(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    // Sorted by hash value.
+    fn new_hashes(
+        old: &EntitiesJson,
+        new: &EntitiesJson,
+        hash_of: fn(&MeshHashesAndEntity) -> RuntimeResourceID,
+    ) -> Vec<RuntimeResourceID> {
+        let old_hashes = old.meshes.iter().map(hash_of).collect::<HashSet<_>>();
+        let mut new_hashes = new
+            .meshes
+            .iter()
+            .map(hash_of)
+            .filter(|hash| !old_hashes.contains(hash))
+            .collect::<Vec<_>>();
+        new_hashes.sort_by_key(ResourceHash::to_u64);
+        new_hashes.dedup();
+        new_hashes
+    }
+
+    fn compare_entities(
+        entity_kind: SceneEntityKind,
+        old_states: Vec<(u64, EntityState)>,
+        new_states: Vec<(u64, EntityState)>,
+        changes: &mut Vec<SceneChange>,
+    ) {
+        let index_by_id = |states: &Vec<(u64, EntityState)>| {
+            let mut indices = HashMap::new();
+            for (index, (id, _)) in states.iter().enumerate() {
+                indices.entry(*id).or_insert(index);
+            }
+            indices
+        };
+        let old_indices = index_by_id(&old_states);
+        let new_indices = index_by_id(&new_states);
+        let ids = old_indices
+            .keys()
+            .chain(new_indices.keys())
+            .copied()
+            .collect::<BTreeSet<_>>();
+        for id in ids {
+            let old_index = old_indices.get(&id).copied();
+            let new_index = new_indices.get(&id).copied();
+            let change_kind = match (old_index, new_index) {
+                (None, Some(_)) => SceneChangeKind::Added,
+                (Some(_), None) => SceneChangeKind::Removed,
+                (Some(old_index), Some(new_index)) => {
+                    let old_state = &old_states[old_index].1;
+                    let new_state = &new_states[new_index].1;
+                    let transformed = !Self::same_transform(old_state, new_state);
+                    let modified = old_state.contents != new_state.contents;
+                    match (transformed, modified) {
+                        (false, false) => continue,
+                        (true, false) => SceneChangeKind::Transformed,
+                        (false, true) => SceneChangeKind::Modified,
+                        (true, true) => SceneChangeKind::TransformedAndModified,
+                    }
+                }
+                (None, None) => continue,
+            };
+            changes.push(SceneChange {
+                entity_kind,
+                change_kind,
+                id,
+                old_index,
+                new_index,
+            });
+        }
+    }
+
+    fn same_transform(old: &EntityState, new: &EntityState) -> bool {
+        let close = |a: f64, b: f64| (a - b).abs() <= TRANSFORM_TOLERANCE;
+        let same_vec3 = |a: &Vec3, b: &Vec3| close(a.x, b.x) && close(a.y, b.y) && close(a.z, b.z);
+        // A quaternion and its negation describe the same rotation.
+        let same_rotation = |a: &Rotation, b: &Rotation, sign: f64| {
+            close(a.x, sign * b.x)
+                && close(a.y, sign * b.y)
+                && close(a.z, sign * b.z)
+                && close(a.w, sign * b.w)
+        };
+        let same_scale = match (old.scale, new.scale) {
+            (Some(old_scale), Some(new_scale)) => same_vec3(old_scale, new_scale),
+            _ => true,
+        };
+        same_vec3(old.position, new.position)
+            && (same_rotation(old.rotation, new.rotation, 1.0)
+                || same_rotation(old.rotation, new.rotation, -1.0))
+            && same_scale
+    }
+}
@@ -0,0 +1,575 @@
+use std::collections::HashMap;
+use std::fs;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::json_serde::entities_json::{
+    deserialize_entity_id, deserialize_resource_id, EntitiesJson, MeshHashesAndEntity, PfBox,
+    PfBoxType, PfSeedPoint, Rotation, Scale, ScaleType, Type, Vec3,
+};
+use crate::json_serde::scene_diff::SceneEntityKind;
+use crate::logging::logger::Logger;
+
+// How far a rotation quaternion's length may stray from 1 before it is reported.
+const UNIT_QUATERNION_TOLERANCE: f64 = 1e-3;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationSeverity {
+    // The scene can still be built, but probably not as intended.
+    Warning,
+    // The scene cannot be built correctly.
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    pub severity: ValidationSeverity,
+    // JSONPath of the offending value, such as `$.meshes[3].alocHash`.
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn count(&self, severity: ValidationSeverity) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(ValidationSeverity::Error) > 0
+    }
+
+    fn warning(&mut self, path: String, message: impl Into<String>) {
+        self.push(ValidationSeverity::Warning, path, message);
+    }
+
+    fn error(&mut self, path: String, message: impl Into<String>) {
+        self.push(ValidationSeverity::Error, path, message);
+    }
+
+    fn push(&mut self, severity: ValidationSeverity, path: String, message: impl Into<String>) {
+        self.issues.push(ValidationIssue {
+            severity,
+            path,
+            message: message.into(),
+        });
+    }
+}
+
+// The checked fields of one mesh entity, pathfinding box or seed point. A field is `None` when
+// it failed to parse, which has already been reported, or when the entity does not have it.
+struct EntityFields {
+    path: String,
+    id: Option<u64>,
+    position: Option<Vec3>,
+    rotation: Option<Rotation>,
+    scale: Option<Scale>,
+    pf_box_type: Option<Type>,
+}
+
+pub struct SceneValidation;
+
+impl SceneValidation {
+    pub fn validate_nav_json_file(
+        nav_json_file: String,
+        logger: &Logger,
+    ) -> Result<ValidationReport, NavKitError> {
+        let logger = logger.with_module(module_path!());
+        let nav_json_string = fs::read_to_string(nav_json_file.as_str()).map_err(|e| {
+            NavKitError::new(
+                NavKitErrorCode::NavJsonRead,
+                format!("Error reading nav.json file: {}", e),
+            )
+            .log(&logger)
+        })?;
+        Ok(Self::validate_nav_json_string(nav_json_string, &logger))
+    }
+
+    // Each field of each mesh, pathfinding box and seed point is parsed separately, so a
+    // malformed value is reported at its own path and does not hide problems in the rest of the
+    // entry or in the others.
+    pub fn validate_nav_json_string(nav_json_string: String, logger: &Logger) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        match serde_json::from_str::<Value>(&nav_json_string) {
+            Ok(root) if root.is_object() => {
+                let meshes = Self::elements(&mut report, &root, "meshes")
+                    .into_iter()
+                    .map(|(path, mesh)| {
+                        let first_issue = report.issues.len();
+                        Self::parse_field_with(&mut report, mesh, &path, "alocHash", |value| {
+                            deserialize_resource_id(value)
+                        });
+                        Self::parse_field_with(&mut report, mesh, &path, "primHash", |value| {
+                            deserialize_resource_id(value)
+                        });
+                        let entity_path = format!("{}.entity", path);
+                        let entity_fields = match mesh.get("entity") {
+                            Some(entity) if entity.is_object() => Self::parse_entity_fields(
+                                &mut report,
+                                entity,
+                                entity_path,
+                                SceneEntityKind::Mesh,
+                            ),
+                            Some(_) => {
+                                report.error(entity_path.clone(), "Expected an object");
+                                Self::unparsed_entity_fields(entity_path)
+                            }
+                            None => {
+                                report.error(entity_path.clone(), "Missing field");
+                                Self::unparsed_entity_fields(entity_path)
+                            }
+                        };
+                        Self::check_remaining_fields::<MeshHashesAndEntity>(
+                            &mut report,
+                            mesh,
+                            &path,
+                            first_issue,
+                        );
+                        entity_fields
+                    })
+                    .collect();
+                let pf_boxes = Self::elements(&mut report, &root, "pfBoxes")
+                    .into_iter()
+                    .map(|(path, pf_box)| {
+                        let first_issue = report.issues.len();
+                        let fields = Self::parse_entity_fields(
+                            &mut report,
+                            pf_box,
+                            path.clone(),
+                            SceneEntityKind::PfBox,
+                        );
+                        Self::check_remaining_fields::<PfBox>(
+                            &mut report,
+                            pf_box,
+                            &path,
+                            first_issue,
+                        );
+                        fields
+                    })
+                    .collect();
+                let pf_seed_points = Self::elements(&mut report, &root, "pfSeedPoints")
+                    .into_iter()
+                    .map(|(path, pf_seed_point)| {
+                        let first_issue = report.issues.len();
+                        let fields = Self::parse_entity_fields(
+                            &mut report,
+                            pf_seed_point,
+                            path.clone(),
+                            SceneEntityKind::PfSeedPoint,
+                        );
+                        Self::check_remaining_fields::<PfSeedPoint>(
+                            &mut report,
+                            pf_seed_point,
+                            &path,
+                            first_issue,
+                        );
+                        fields
+                    })
+                    .collect();
+                Self::check_scene(&mut report, meshes, pf_boxes, pf_seed_points);
+            }
+            Ok(_) => report.error("$".to_string(), "nav.json root must be an object"),
+            Err(e) => report.error(
+                "$".to_string(),
+                format!("Error parsing nav.json file: {}", e),
+            ),
+        }
+        Self::log_report(&report, logger);
+        report
+    }
+
+    pub fn validate(entities_json: &EntitiesJson, logger: &Logger) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        Self::check_scene(
+            &mut report,
+            entities_json
+                .meshes
+                .iter()
+                .enumerate()
+                .map(|(index, mesh)| Self::mesh_fields(index, mesh))
+                .collect(),
+            entities_json
+                .pf_boxes
+                .iter()
+                .enumerate()
+                .map(|(index, pf_box)| Self::pf_box_fields(index, pf_box))
+                .collect(),
+            entities_json
+                .pf_seed_points
+                .iter()
+                .enumerate()
+                .map(|(index, pf_seed_point)| Self::pf_seed_point_fields(index, pf_seed_point))
+                .collect(),
+        );
+        Self::log_report(&report, logger);
+        report
+    }
+
+    // Returns the object entries of the `key` array, paired with their JSON path.
+    fn elements<'a>(
+        report: &mut ValidationReport,
+        root: &'a Value,
+        key: &str,
+    ) -> Vec<(String, &'a Value)> {
+        let path = format!("$.{}", key);
+        let elements = match root.get(key) {
+            Some(Value::Array(elements)) => elements,
+            Some(_) => {
+                report.error(path, "Expected an array");
+                return Vec::new();
+            }
+            None => {
+                report.error(path, "Missing array");
+                return Vec::new();
+            }
+        };
+        elements
+            .iter()
+            .enumerate()
+            .filter_map(|(index, element)| {
+                let element_path = format!("{}[{}]", path, index);
+                if element.is_object() {
+                    Some((element_path, element))
+                } else {
+                    report.error(element_path, "Expected an object");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn parse_entity_fields(
+        report: &mut ValidationReport,
+        entity: &Value,
+        path: String,
+        kind: SceneEntityKind,
+    ) -> EntityFields {
+        EntityFields {
+            id: Self::parse_field_with(report, entity, &path, "id", |value| {
+                deserialize_entity_id(value)
+            }),
+            position: Self::parse_field(report, entity, &path, "position"),
+            rotation: Self::parse_field(report, entity, &path, "rotation"),
+            // Seed points have no scale.
+            scale: if kind == SceneEntityKind::PfSeedPoint {
+                None
+            } else {
+                Self::parse_field(report, entity, &path, "scale")
+            },
+            pf_box_type: if kind == SceneEntityKind::PfBox {
+                Self::parse_field(report, entity, &path, "type")
+            } else {
+                None
+            },
+            path,
+        }
+    }
+
+    fn unparsed_entity_fields(path: String) -> EntityFields {
+        EntityFields {
+            path,
+            id: None,
+            position: None,
+            rotation: None,
+            scale: None,
+            pf_box_type: None,
+        }
+    }
+
+    fn parse_field<T: DeserializeOwned>(
+        report: &mut ValidationReport,
+        element: &Value,
+        path: &str,
+        key: &str,
+    ) -> Option<T> {
+        Self::parse_field_with(report, element, path, key, |value| T::deserialize(value))
+    }
+
+    fn parse_field_with<T>(
+        report: &mut ValidationReport,
+        element: &Value,
+        path: &str,
+        key: &str,
+        parse: impl FnOnce(&Value) -> Result<T, serde_json::Error>,
+    ) -> Option<T> {
+        let path = format!("{}.{}", path, key);
+        match element.get(key) {
+            Some(value) => parse(value)
+                .map_err(|e| report.error(path, e.to_string()))
+                .ok(),
+            None => {
+                report.error(path, "Missing field");
+                None
+            }
+        }
+    }
+
+    // Reports problems in fields the checks do not look at, such as a mesh name that is not a
+    // string, unless the entry already has errors among the issues from `first_issue` on.
+    fn check_remaining_fields<T: DeserializeOwned>(
+        report: &mut ValidationReport,
+        element: &Value,
+        path: &str,
+        first_issue: usize,
+    ) {
+        let element_has_errors = report.issues[first_issue..]
+            .iter()
+            .any(|issue| issue.severity == ValidationSeverity::Error);
+        if element_has_errors {
+            return;
+        }
+        if let Err(e) = T::deserialize(element) {
+            report.error(path.to_string(), e.to_string());
+        }
+    }
+
+    fn mesh_fields(index: usize, mesh: &MeshHashesAndEntity) -> EntityFields {
+        EntityFields {
+            path: format!("$.meshes[{}].entity", index),
+            id: Some(mesh.entity.id),
+            position: Some(mesh.entity.position),
+            rotation: Some(mesh.entity.rotation),
+            scale: Some(mesh.entity.scale.clone()),
+            pf_box_type: None,
+        }
+    }
+
+    fn pf_box_fields(index: usize, pf_box: &PfBox) -> EntityFields {
+        EntityFields {
+            path: format!("$.pfBoxes[{}]", index),
+            id: Some(pf_box.id),
+            position: Some(pf_box.position),
+            rotation: Some(pf_box.rotation),
+            scale: Some(pf_box.scale.clone()),
+            pf_box_type: Some(pf_box.r#type.clone()),
+        }
+    }
+
+    fn pf_seed_point_fields(index: usize, pf_seed_point: &PfSeedPoint) -> EntityFields {
+        EntityFields {
+            path: format!("$.pfSeedPoints[{}]", index),
+            id: Some(pf_seed_point.id),
+            position: Some(pf_seed_point.position),
+            rotation: Some(pf_seed_point.rotation),
+            scale: None,
+            pf_box_type: None,
+        }
+    }
+
+    fn check_scene(
+        report: &mut ValidationReport,
+        meshes: Vec<EntityFields>,
+        pf_boxes: Vec<EntityFields>,
+        pf_seed_points: Vec<EntityFields>,
+    ) {
+        let mut entity_paths: HashMap<u64, String> = HashMap::new();
+
+        if meshes.is_empty() {
+            report.warning("$.meshes".to_string(), "Scene has no meshes");
+        }
+        for fields in meshes.iter().chain(&pf_boxes).chain(&pf_seed_points) {
+            if let Some(id) = fields.id {
+                Self::check_id(report, &mut entity_paths, &fields.path, id);
+            }
+            if let Some(position) = &fields.position {
+                Self::check_position(report, &fields.path, position);
+            }
+            if let Some(rotation) = &fields.rotation {
+                Self::check_rotation(report, &fields.path, rotation);
+            }
+            if let Some(scale) = &fields.scale {
+                Self::check_scale(report, &fields.path, scale);
+            }
+            if let Some(pf_box_type) = &fields.pf_box_type {
+                Self::check_pf_box_type(report, &fields.path, pf_box_type);
+            }
+        }
+    }
+
+    fn log_report(report: &ValidationReport, logger: &Logger) {
+        let logger = logger.with_module(module_path!());
+        for issue in &report.issues {
+            let message = format!("{}: {}", issue.path, issue.message);
+            match issue.severity {
+                ValidationSeverity::Warning => logger.warn(message),
+                ValidationSeverity::Error => logger.error(message),
+            }
+        }
+        logger.info(format!(
+            "Validated nav.json: {} errors, {} warnings.",
+            report.count(ValidationSeverity::Error),
+            report.count(ValidationSeverity::Warning)
+        ));
+    }
+
+    fn check_id(
+        report: &mut ValidationReport,
+        entity_paths: &mut HashMap<u64, String>,
+        entity_path: &str,
+        id: u64,
+    ) {
+        if let Some(first_path) = entity_paths.get(&id) {
+            report.error(
+                format!("{}.id", entity_path),
+                format!(
+                    "Duplicate entity id {:016x}, first used at {}",
+                    id, first_path
+                ),
+            );
+        } else {
+            entity_paths.insert(id, entity_path.to_string());
+        }
+    }
+
+    fn check_position(report: &mut ValidationReport, entity_path: &str, position: &Vec3) {
+        if ![position.x, position.y, position.z]
+            .iter()
+            .all(|component| component.is_finite())
+        {
+            report.error(
+                format!("{}.position", entity_path),
+                "Position has a NaN or infinite component",
+            );
+        }
+    }
+
+    fn check_rotation(report: &mut ValidationReport, entity_path: &str, rotation: &Rotation) {
+        let path = format!("{}.rotation", entity_path);
+        let components = [rotation.x, rotation.y, rotation.z, rotation.w];
+        if !components.iter().all(|component| component.is_finite()) {
+            report.error(path, "Rotation has a NaN or infinite component");
+            return;
+        }
+        let length = components
+            .iter()
+            .map(|component| component * component)
+            .sum::<f64>()
+            .sqrt();
+        if length == 0.0 {
+            report.error(path, "Rotation quaternion has zero length");
+        } else if (length - 1.0).abs() > UNIT_QUATERNION_TOLERANCE {
+            report.warning(
+                path,
+                format!("Rotation quaternion is not normalized (length {})", length),
+            );
+        }
+    }
+
+    fn check_scale(report: &mut ValidationReport, entity_path: &str, scale: &Scale) {
+        let path = format!("{}.scale", entity_path);
+        if let ScaleType::Unknown(name) = &scale.r#type {
+            report.warning(
+                format!("{}.type", path),
+                format!("Unknown scale type '{}'", name),
+            );
+        }
+        let components = [scale.data.x, scale.data.y, scale.data.z];
+        if !components.iter().all(|component| component.is_finite()) {
+            report.error(
+                format!("{}.data", path),
+                "Scale has a NaN or infinite component",
+            );
+        } else if components.contains(&0.0) {
+            report.error(format!("{}.data", path), "Scale has a zero component");
+        }
+    }
+
+    fn check_pf_box_type(report: &mut ValidationReport, entity_path: &str, pf_box_type: &Type) {
+        if let PfBoxType::Unknown(name) = &pf_box_type.data {
+            report.error(
+                format!("{}.type.data", entity_path),
+                format!("Unknown pathfinding box type '{}'", name),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn errors_of(nav_json: Value) -> Vec<(String, String)> {
+        SceneValidation::validate_nav_json_string(
+            nav_json.to_string(),
+            &Logger::new(None, module_path!()),
+        )
+        .issues
+        .into_iter()
+        .filter(|issue| issue.severity == ValidationSeverity::Error)
+        .map(|issue| (issue.path, issue.message))
+        .collect()
+    }
+
+    fn position() -> Value {
+        json!({"x": 0.0, "y": 0.0, "z": 0.0})
+    }
+
+    fn rotation() -> Value {
+        json!({"x": 0.0, "y": 0.0, "z": 0.0, "w": 1.0})
+    }
+
+    fn scale() -> Value {
+        json!({"type": "SVector3", "data": {"x": 1.0, "y": 1.0, "z": 1.0}})
+    }
+
+    #[test]
+    fn malformed_ids_are_reported_at_their_field_path() {
+        let errors = errors_of(json!({
+            "meshes": [{
+                "alocHash": "zz",
+                "primHash": "00290D5B143172A3",
+                "entity": {
+                    "id": "0000000000000001",
+                    "position": position(),
+                    "rotation": rotation(),
+                    "scale": scale(),
+                },
+            }],
+            "pfBoxes": [{
+                "id": "12",
+                "position": position(),
+                "rotation": rotation(),
+                "scale": scale(),
+                "type": {"type": "EPathFinder_PFBoxType", "data": "PFBT_NEW_TYPE"},
+            }],
+            "pfSeedPoints": [{
+                "id": "0xzz",
+                "position": position(),
+                "rotation": rotation(),
+            }],
+        }));
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    "$.meshes[0].alocHash".to_string(),
+                    "malformed hash 'zz'".to_string()
+                ),
+                (
+                    "$.pfBoxes[0].id".to_string(),
+                    "malformed entity id '12': expected 16 hex digits or a 0x prefixed hex number"
+                        .to_string()
+                ),
+                (
+                    "$.pfSeedPoints[0].id".to_string(),
+                    "malformed entity id '0xzz'".to_string()
+                ),
+                (
+                    "$.pfBoxes[0].type.data".to_string(),
+                    "Unknown pathfinding box type 'PFBT_NEW_TYPE'".to_string()
+                ),
+            ]
+        );
+    }
+}
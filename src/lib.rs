@@ -1,15 +1,110 @@
 extern crate core;
 
+pub mod bundle;
+pub mod error;
 pub mod extract;
+pub mod geometry;
 pub mod json_serde;
+pub mod logging;
 pub mod package;
+#[cfg(feature = "path-list")]
+pub mod path_list;
+pub mod progress;
 
+use crate::bundle::resource_bundle::{BundleEntry, ResourceBundle};
+use crate::error::navkit_error::{
+    last_error_code, last_error_message, to_error_code, NavKitError, NavKitErrorCode,
+};
+use crate::extract::dependency_closure::DependencyClosure;
+use crate::extract::dependency_options::DependencyOptions;
+use crate::extract::extraction_options::{ExtractionOptions, OutputMode};
+use crate::extract::extraction_result::{ExtractionOutcome, ExtractionResult, ExtractionStatus};
 use crate::extract::rpkg_extraction::RpkgExtraction;
-use crate::json_serde::entities_json::EntitiesJson;
+use crate::geometry::bounding_box::BoundingBox;
+use crate::geometry::matrix4::Matrix4;
+use crate::geometry::mesh_filter::{MeshFilter, MeshFilterOptions, MeshFilterStatus};
+use crate::geometry::oriented_bounding_box::OrientedBoundingBox;
+use crate::geometry::scene_bounds::SceneBounds;
+use crate::json_serde::entities_json::{
+    EntitiesJson, MeshHashesAndEntity, PfBox, PfBoxKind, PfSeedPoint, Rotation, ScaleKind, Vec3,
+};
+use crate::json_serde::scene_diff::{SceneChangeKind, SceneDiff, SceneEntityKind};
+use crate::json_serde::scene_validation::{
+    SceneValidation, ValidationIssue, ValidationReport, ValidationSeverity,
+};
+use crate::logging::logger::{LogLevel, Logger};
 use crate::package::package_scan::PackageScan;
+use crate::package::resource_hash::ResourceHash;
+use crate::package::resource_query::{PathPattern, ResourceQuery};
+use crate::package::resource_search::{ResourceSearch, ResourceSearchHit, ResourceSearchResult};
+use crate::package::resource_type::ResourceType;
+use crate::package::scan_options::ScanOptions;
+#[cfg(feature = "path-list")]
+use crate::path_list::hash_list::HashList;
+use crate::progress::cancellation_token::CancellationToken;
+use crate::progress::progress_report::ProgressPhase;
 use std::collections::HashSet;
 use std::ffi::{CStr, CString};
-use std::os::raw::c_char;
+use std::os::raw::{c_char, c_void};
+use std::path::Path;
+
+fn string_from_c_str(ptr: *const c_char, argument_name: &str) -> Result<String, NavKitError> {
+    if ptr.is_null() {
+        return Err(NavKitError::new(
+            NavKitErrorCode::NullArgument,
+            format!("Argument '{}' must not be null", argument_name),
+        ));
+    }
+    Ok(unsafe { CStr::from_ptr(ptr).to_string_lossy().into_owned() })
+}
+
+fn strings_from_c_array(strings: *const *const c_char, strings_len: usize) -> Vec<String> {
+    if strings.is_null() {
+        return Vec::new();
+    }
+    unsafe { std::slice::from_raw_parts(strings, strings_len) }
+        .iter()
+        .filter(|ptr| !ptr.is_null())
+        .map(|&ptr| unsafe { CStr::from_ptr(ptr).to_string_lossy().into_owned() })
+        .collect()
+}
+
+fn check_not_null<T>(ptr: *const T, argument_name: &str) -> Result<(), NavKitError> {
+    if ptr.is_null() {
+        return Err(NavKitError::new(
+            NavKitErrorCode::NullArgument,
+            format!("Argument '{}' must not be null", argument_name),
+        ));
+    }
+    Ok(())
+}
+
+#[no_mangle]
+pub extern "C" fn navkit_last_error_code() -> NavKitErrorCode {
+    last_error_code()
+}
+
+#[no_mangle]
+pub extern "C" fn navkit_last_error_message() -> *const c_char {
+    last_error_message()
+}
+
+#[no_mangle]
+pub extern "C" fn navkit_register_logger(
+    callback: Option<extern "C" fn(LogLevel, *const c_char, *const c_char, *mut c_void)>,
+    user_data: *mut c_void,
+    min_level: LogLevel,
+) {
+    match callback {
+        Some(callback) => Logger::register(callback, user_data, min_level),
+        None => Logger::unregister(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn navkit_unregister_logger() {
+    Logger::unregister();
+}
 
 #[no_mangle]
 pub extern "C" fn extract_scene_mesh_resources(
@@ -18,77 +113,103 @@ pub extern "C" fn extract_scene_mesh_resources(
     partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
     output_directory: *const c_char,
     output_type: *const c_char,
-    log_callback: extern "C" fn(*const c_char),
-) -> std::ffi::c_int {
-    let output_directory_str = unsafe {
-        CStr::from_ptr(output_directory)
-            .to_string_lossy()
-            .into_owned()
-    };
-    let output_type_str = unsafe { CStr::from_ptr(output_type).to_string_lossy().into_owned() };
-
-    let msg = CString::new("navkit-rpkg-lib - Starting extraction from RPKG files.").unwrap();
-    log_callback(msg.as_ptr());
-
-    let runtime_directory_ref = unsafe {
-        CStr::from_ptr(runtime_directory)
-            .to_string_lossy()
-            .into_owned()
-    };
-    let nav_json = match EntitiesJson::build_from_nav_json_file(
-        unsafe { CStr::from_ptr(nav_json_file).to_string_lossy().into_owned() }.clone(),
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    progress_callback: Option<extern "C" fn(ProgressPhase, usize, usize, *const c_char)>,
+    cancellation_token: *const CancellationToken,
+) -> NavKitErrorCode {
+    extract_scene_mesh_resources_with_options(
+        nav_json_file,
+        runtime_directory,
+        partition_manager,
+        output_directory,
+        output_type,
+        std::ptr::null(),
         log_callback,
-    ) {
-        Some(json) => json,
-        None => {
-            return -1;
-        }
-    };
-    let needed_aloc_or_prim_hashes = RpkgExtraction::get_needed_aloc_or_prim_hashes_from_scene(
-        &nav_json,
-        output_type_str.clone(),
-    );
-    let mut result: std::ffi::c_int = 0;
-
-    if needed_aloc_or_prim_hashes.is_empty() {
-        let msg = CString::new(format!(
-            "All {} files already exist. Skipping extraction.",
-            output_type_str
-        ))
-        .unwrap();
-        log_callback(msg.as_ptr());
-        return result;
-    }
-    let msg = CString::new(format!(
-        "Extracting {} {}s.",
-        needed_aloc_or_prim_hashes.len(),
-        output_type_str
-    ))
-    .unwrap();
-    log_callback(msg.as_ptr());
-
-    let needed_aloc_or_prim_hash_strings: Vec<std::ffi::CString> = needed_aloc_or_prim_hashes
-        .iter()
-        .map(|s| std::ffi::CString::new(s.as_str()).unwrap())
-        .collect();
-    let needed_aloc_or_prim_hashes_c_ptrs: Vec<*const c_char> = needed_aloc_or_prim_hash_strings
-        .iter()
-        .map(|c| c.as_ptr())
-        .collect();
+        progress_callback,
+        cancellation_token,
+        std::ptr::null_mut(),
+    )
+}
 
-    let partition_manager_ref = unsafe { &*partition_manager };
-    unsafe {
-        result = RpkgExtraction::extract_resources_from_rpkg(
-            runtime_directory_ref.clone(),
-            needed_aloc_or_prim_hashes_c_ptrs.as_ptr(),
-            needed_aloc_or_prim_hashes_c_ptrs.len(),
-            partition_manager_ref,
-            output_directory_str.clone(),
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn extract_scene_mesh_resources_with_options(
+    nav_json_file: *const c_char,
+    runtime_directory: *const c_char,
+    partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
+    output_directory: *const c_char,
+    output_type: *const c_char,
+    extraction_options: *const NavKitExtractionOptions,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    progress_callback: Option<extern "C" fn(ProgressPhase, usize, usize, *const c_char)>,
+    cancellation_token: *const CancellationToken,
+    out_result: *mut *mut ExtractionResult,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let output_directory_str = string_from_c_str(output_directory, "output_directory")?;
+        let output_type_str = string_from_c_str(output_type, "output_type")?;
+        let runtime_directory_ref = string_from_c_str(runtime_directory, "runtime_directory")?;
+        let nav_json_file_str = string_from_c_str(nav_json_file, "nav_json_file")?;
+        check_not_null(partition_manager, "partition_manager")?;
+
+        logger.info("navkit-rpkg-lib - Starting extraction from RPKG files.");
+
+        let extraction_options = extraction_options_from_c(extraction_options);
+        let nav_json = EntitiesJson::build_from_nav_json_file(nav_json_file_str, &logger)?;
+        CancellationToken::check(unsafe { cancellation_token.as_ref() })?;
+        let meshes = match &extraction_options.mesh_filter {
+            Some(mesh_filter_options) => MeshFilter::filter(
+                &nav_json,
+                &runtime_directory_ref,
+                unsafe { &*partition_manager },
+                mesh_filter_options,
+                &logger,
+            ),
+            None => nav_json.meshes.iter().collect(),
+        };
+        if meshes.is_empty() && !nav_json.meshes.is_empty() {
+            logger.info(format!(
+                "The mesh filter removed all {} meshes. Skipping extraction.",
+                nav_json.meshes.len()
+            ));
+            return store_extraction_result(ExtractionResult::default(), out_result);
+        }
+        let needed_aloc_or_prim_hashes = RpkgExtraction::get_needed_aloc_or_prim_hashes_from_meshes(
+            meshes,
             output_type_str.clone(),
-            log_callback,
         );
-    }
-    result
+
+        if needed_aloc_or_prim_hashes.is_empty() {
+            logger.info(format!(
+                "All {} files already exist. Skipping extraction.",
+                output_type_str
+            ));
+            return store_extraction_result(ExtractionResult::default(), out_result);
+        }
+        logger.info(format!(
+            "Extracting {} {}s.",
+            needed_aloc_or_prim_hashes.len(),
+            output_type_str
+        ));
+
+        let needed_aloc_or_prim_hashes = needed_aloc_or_prim_hashes
+            .into_iter()
+            .collect::<Vec<String>>();
+        let partition_manager_ref = unsafe { &*partition_manager };
+        let extraction_result = RpkgExtraction::extract_resources_from_rpkg_with_options(
+            runtime_directory_ref,
+            &needed_aloc_or_prim_hashes,
+            partition_manager_ref,
+            output_directory_str,
+            output_type_str,
+            &extraction_options,
+            &logger,
+            progress_callback,
+            unsafe { cancellation_token.as_ref() },
+        )?;
+        store_extraction_result(extraction_result, out_result)
+    })())
 }
 
 #[no_mangle]
@@ -99,29 +220,238 @@ pub extern "C" fn extract_resources_from_rpkg(
     partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
     output_folder: *const c_char,
     resource_type: *const c_char,
-    log_callback: extern "C" fn(*const c_char),
-) {
-    let msg = std::ffi::CString::new("Extracting Resources from rpkg.".to_string()).unwrap();
-    log_callback(msg.as_ptr());
-    let runtime_folder_str = unsafe {
-        CStr::from_ptr(runtime_folder)
-            .to_string_lossy()
-            .into_owned()
-    };
-
-    let partition_manager_ref = unsafe { &*partition_manager };
-    let output_folder_str = unsafe { CStr::from_ptr(output_folder).to_string_lossy().into_owned() };
-    let resource_type_str = unsafe { CStr::from_ptr(resource_type).to_string_lossy().into_owned() };
-    unsafe {
-        RpkgExtraction::extract_resources_from_rpkg(
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    progress_callback: Option<extern "C" fn(ProgressPhase, usize, usize, *const c_char)>,
+    cancellation_token: *const CancellationToken,
+) -> NavKitErrorCode {
+    extract_resources_from_rpkg_with_options(
+        runtime_folder,
+        needed_hashes,
+        needed_hashes_len,
+        partition_manager,
+        output_folder,
+        resource_type,
+        std::ptr::null(),
+        log_callback,
+        progress_callback,
+        cancellation_token,
+        std::ptr::null_mut(),
+    )
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn extract_resources_from_rpkg_with_options(
+    runtime_folder: *const c_char,
+    needed_hashes: *const *const c_char,
+    needed_hashes_len: usize,
+    partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
+    output_folder: *const c_char,
+    resource_type: *const c_char,
+    extraction_options: *const NavKitExtractionOptions,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    progress_callback: Option<extern "C" fn(ProgressPhase, usize, usize, *const c_char)>,
+    cancellation_token: *const CancellationToken,
+    out_result: *mut *mut ExtractionResult,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let runtime_folder_str = string_from_c_str(runtime_folder, "runtime_folder")?;
+        let output_folder_str = string_from_c_str(output_folder, "output_folder")?;
+        let resource_type_str = string_from_c_str(resource_type, "resource_type")?;
+        check_not_null(partition_manager, "partition_manager")?;
+
+        logger.info("Extracting Resources from rpkg.");
+
+        let needed_hashes_list = strings_from_c_array(needed_hashes, needed_hashes_len);
+        let partition_manager_ref = unsafe { &*partition_manager };
+        let extraction_result = RpkgExtraction::extract_resources_from_rpkg_with_options(
             runtime_folder_str,
-            needed_hashes,
-            needed_hashes_len,
+            &needed_hashes_list,
             partition_manager_ref,
             output_folder_str,
             resource_type_str,
-            log_callback,
+            &extraction_options_from_c(extraction_options),
+            &logger,
+            progress_callback,
+            unsafe { cancellation_token.as_ref() },
+        )?;
+        store_extraction_result(extraction_result, out_result)
+    })())
+}
+
+#[repr(C)]
+pub struct NavKitExtractionOptions {
+    thread_count: usize,
+    write_manifest: bool,
+    write_meta_json: bool,
+    output_mode: OutputMode,
+    // Null uses the default bundle file name.
+    bundle_file_name: *const c_char,
+    compress_bundle: bool,
+    filter_meshes_by_pf_boxes: bool,
+    mesh_filter: MeshFilterOptions,
+}
+
+#[no_mangle]
+pub extern "C" fn get_default_extraction_options() -> NavKitExtractionOptions {
+    let defaults = ExtractionOptions::default();
+    NavKitExtractionOptions {
+        thread_count: defaults.thread_count,
+        write_manifest: defaults.write_manifest,
+        write_meta_json: defaults.write_meta_json,
+        output_mode: defaults.output_mode,
+        bundle_file_name: std::ptr::null(),
+        compress_bundle: defaults.compress_bundle,
+        filter_meshes_by_pf_boxes: defaults.mesh_filter.is_some(),
+        mesh_filter: defaults.mesh_filter.unwrap_or_default(),
+    }
+}
+
+fn extraction_options_from_c(options: *const NavKitExtractionOptions) -> ExtractionOptions {
+    let defaults = ExtractionOptions::default();
+    match unsafe { options.as_ref() } {
+        Some(options_ref) => ExtractionOptions {
+            thread_count: options_ref.thread_count,
+            write_manifest: options_ref.write_manifest,
+            write_meta_json: options_ref.write_meta_json,
+            output_mode: options_ref.output_mode,
+            bundle_file_name: if options_ref.bundle_file_name.is_null() {
+                defaults.bundle_file_name
+            } else {
+                unsafe { CStr::from_ptr(options_ref.bundle_file_name) }
+                    .to_string_lossy()
+                    .into_owned()
+            },
+            compress_bundle: options_ref.compress_bundle,
+            mesh_filter: options_ref
+                .filter_meshes_by_pf_boxes
+                .then_some(options_ref.mesh_filter),
+        },
+        None => defaults,
+    }
+}
+
+// Hands the result to the caller when `out_result` is set. Otherwise any failed resource turns
+// into the returned error code.
+fn store_extraction_result(
+    extraction_result: ExtractionResult,
+    out_result: *mut *mut ExtractionResult,
+) -> Result<(), NavKitError> {
+    if out_result.is_null() {
+        return extraction_result.into_error();
+    }
+    unsafe {
+        *out_result = Box::into_raw(Box::new(extraction_result));
+    }
+    Ok(())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn extract_dependency_closure_from_rpkg(
+    runtime_folder: *const c_char,
+    root_hashes: *const *const c_char,
+    root_hashes_len: usize,
+    partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
+    output_folder: *const c_char,
+    dependency_options: *const NavKitDependencyOptions,
+    extraction_options: *const NavKitExtractionOptions,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    progress_callback: Option<extern "C" fn(ProgressPhase, usize, usize, *const c_char)>,
+    cancellation_token: *const CancellationToken,
+    out_result: *mut *mut ExtractionResult,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let runtime_folder_str = string_from_c_str(runtime_folder, "runtime_folder")?;
+        let output_folder_str = string_from_c_str(output_folder, "output_folder")?;
+        check_not_null(partition_manager, "partition_manager")?;
+
+        logger.info("Extracting Resources and their dependencies from rpkg.");
+
+        let root_hashes_list = strings_from_c_array(root_hashes, root_hashes_len);
+        let partition_manager_ref = unsafe { &*partition_manager };
+        let extraction_result = RpkgExtraction::extract_dependency_closure_from_rpkg(
+            runtime_folder_str,
+            &root_hashes_list,
+            partition_manager_ref,
+            output_folder_str,
+            &dependency_options_from_c(dependency_options),
+            &extraction_options_from_c(extraction_options),
+            &logger,
+            progress_callback,
+            unsafe { cancellation_token.as_ref() },
+        )?;
+        store_extraction_result(extraction_result, out_result)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_dependency_closure_from_rpkg_files(
+    root_hashes: *const *const c_char,
+    root_hashes_len: usize,
+    partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
+    dependency_options: *const NavKitDependencyOptions,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_list: *mut *mut RustStringList,
+    out_failures: *mut *mut ExtractionResult,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        check_not_null(partition_manager, "partition_manager")?;
+        check_not_null(out_list, "out_list")?;
+
+        let root_hashes_list = strings_from_c_array(root_hashes, root_hashes_len);
+        let partition_manager_ref = unsafe { &*partition_manager };
+        let closure = DependencyClosure::collect(
+            &root_hashes_list,
+            partition_manager_ref,
+            &dependency_options_from_c(dependency_options),
+            &logger,
         );
+        // Without `out_failures` an unresolved root fails the call, as extraction does without
+        // `out_result`.
+        store_extraction_result(closure.failures, out_failures)?;
+        unsafe {
+            *out_list = create_string_list(closure.hashes);
+        }
+        Ok(())
+    })())
+}
+
+#[repr(C)]
+pub struct NavKitDependencyOptions {
+    max_depth: usize,
+    included_types: *const *const c_char,
+    included_types_len: usize,
+}
+
+#[no_mangle]
+pub extern "C" fn get_default_dependency_options() -> NavKitDependencyOptions {
+    let defaults = DependencyOptions::default();
+    NavKitDependencyOptions {
+        max_depth: defaults.max_depth,
+        included_types: std::ptr::null(),
+        included_types_len: 0,
+    }
+}
+
+fn dependency_options_from_c(options: *const NavKitDependencyOptions) -> DependencyOptions {
+    match unsafe { options.as_ref() } {
+        Some(options_ref) => DependencyOptions {
+            max_depth: options_ref.max_depth,
+            included_types: if options_ref.included_types.is_null() {
+                None
+            } else {
+                Some(strings_from_c_array(
+                    options_ref.included_types,
+                    options_ref.included_types_len,
+                ))
+            },
+        },
+        None => DependencyOptions::default(),
     }
 }
 
@@ -129,91 +459,2014 @@ pub extern "C" fn extract_resources_from_rpkg(
 pub extern "C" fn scan_packages(
     retail_folder: *const c_char,
     game_version: *const c_char,
-    log_callback: extern "C" fn(*const c_char),
-) -> *mut rpkg_rs::resource::partition_manager::PartitionManager {
-    let retail_folder_str = unsafe { CStr::from_ptr(retail_folder).to_string_lossy().into_owned() };
-    let game_version_str = unsafe { CStr::from_ptr(game_version).to_string_lossy().into_owned() };
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    progress_callback: Option<extern "C" fn(ProgressPhase, usize, usize, *const c_char)>,
+    cancellation_token: *const CancellationToken,
+    out_partition_manager: *mut *mut rpkg_rs::resource::partition_manager::PartitionManager,
+) -> NavKitErrorCode {
+    scan_packages_with_options(
+        retail_folder,
+        game_version,
+        std::ptr::null(),
+        log_callback,
+        progress_callback,
+        cancellation_token,
+        out_partition_manager,
+    )
+}
+
+#[repr(C)]
+pub struct NavKitScanOptions {
+    max_patch_level: usize,
+    keep_package_definition_patch_levels: bool,
+    included_partitions: *const *const c_char,
+    included_partitions_len: usize,
+    excluded_partitions: *const *const c_char,
+    excluded_partitions_len: usize,
+}
+
+#[no_mangle]
+pub extern "C" fn get_default_scan_options() -> NavKitScanOptions {
+    let defaults = ScanOptions::default();
+    NavKitScanOptions {
+        max_patch_level: defaults.max_patch_level,
+        keep_package_definition_patch_levels: defaults.keep_package_definition_patch_levels,
+        included_partitions: std::ptr::null(),
+        included_partitions_len: 0,
+        excluded_partitions: std::ptr::null(),
+        excluded_partitions_len: 0,
+    }
+}
 
-    let pm = PackageScan::scan_packages(retail_folder_str, game_version_str, log_callback);
-    match pm {
-        Some(manager) => Box::into_raw(Box::new(manager)),
-        None => std::ptr::null_mut(),
+fn scan_options_from_c(options: *const NavKitScanOptions) -> ScanOptions {
+    match unsafe { options.as_ref() } {
+        Some(options_ref) => ScanOptions {
+            max_patch_level: options_ref.max_patch_level,
+            keep_package_definition_patch_levels: options_ref.keep_package_definition_patch_levels,
+            included_partitions: if options_ref.included_partitions.is_null() {
+                None
+            } else {
+                Some(strings_from_c_array(
+                    options_ref.included_partitions,
+                    options_ref.included_partitions_len,
+                ))
+            },
+            excluded_partitions: strings_from_c_array(
+                options_ref.excluded_partitions,
+                options_ref.excluded_partitions_len,
+            ),
+        },
+        None => ScanOptions::default(),
     }
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn scan_packages_with_options(
+    retail_folder: *const c_char,
+    game_version: *const c_char,
+    scan_options: *const NavKitScanOptions,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    progress_callback: Option<extern "C" fn(ProgressPhase, usize, usize, *const c_char)>,
+    cancellation_token: *const CancellationToken,
+    out_partition_manager: *mut *mut rpkg_rs::resource::partition_manager::PartitionManager,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let retail_folder_str = string_from_c_str(retail_folder, "retail_folder")?;
+        let game_version_str = string_from_c_str(game_version, "game_version")?;
+        check_not_null(out_partition_manager, "out_partition_manager")?;
+
+        let manager = PackageScan::scan_packages_with_options(
+            retail_folder_str,
+            game_version_str,
+            &scan_options_from_c(scan_options),
+            &logger,
+            progress_callback,
+            unsafe { cancellation_token.as_ref() },
+        )?;
+        let manager = Box::new(manager);
+        ResourceType::remember_mounted(&manager);
+        unsafe {
+            *out_partition_manager = Box::into_raw(manager);
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn get_all_resources_hashes_by_type_from_rpkg_files(
     partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
     resource_type: *const c_char,
-    log_callback: extern "C" fn(*const c_char),
-) -> *mut RustStringList {
-    let resource_type_str = unsafe { CStr::from_ptr(resource_type).to_string_lossy().into_owned() };
-    let msg = std::ffi::CString::new(
-        format!(
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_list: *mut *mut RustStringList,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let resource_type_str = string_from_c_str(resource_type, "resource_type")?;
+        check_not_null(partition_manager, "partition_manager")?;
+        check_not_null(out_list, "out_list")?;
+
+        logger.info(format!(
             "Scanning Rpkg files for all {} Resources.",
             resource_type_str
-        )
-        .to_string(),
-    )
-    .unwrap();
-    log_callback(msg.as_ptr());
+        ));
 
-    let partition_manager_ref = unsafe { &*partition_manager };
-    let resources = RpkgExtraction::get_all_resources_hashes_by_type_from_rpkg_files(
-        partition_manager_ref,
-        resource_type_str,
-        log_callback,
-    );
-    create_string_list(resources)
+        let partition_manager_ref = unsafe { &*partition_manager };
+        let resources = RpkgExtraction::get_all_resources_hashes_by_type_from_rpkg_files(
+            partition_manager_ref,
+            resource_type_str,
+            &logger,
+        );
+        unsafe {
+            *out_list = create_string_list(resources);
+        }
+        Ok(())
+    })())
+}
+
+// Fills `out_paths` in the same order as `out_hashes`, with an empty string where no path is
+// known.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_all_resources_hashes_and_paths_by_type_from_rpkg_files(
+    partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
+    resource_type: *const c_char,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_hashes: *mut *mut RustStringList,
+    out_paths: *mut *mut RustStringList,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let resource_type_str = string_from_c_str(resource_type, "resource_type")?;
+        check_not_null(partition_manager, "partition_manager")?;
+        check_not_null(out_hashes, "out_hashes")?;
+        check_not_null(out_paths, "out_paths")?;
+
+        let partition_manager_ref = unsafe { &*partition_manager };
+        let (hashes, paths): (Vec<String>, Vec<String>) =
+            RpkgExtraction::get_all_resources_hashes_and_paths_by_type_from_rpkg_files(
+                partition_manager_ref,
+                resource_type_str,
+                &logger,
+            )
+            .into_iter()
+            .map(|(hash, path)| (hash, path.unwrap_or_default()))
+            .unzip();
+        unsafe {
+            *out_hashes = create_string_list(hashes);
+            *out_paths = create_string_list(paths);
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_all_resource_hash_values_by_type_from_rpkg_files(
+    partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
+    resource_type: *const c_char,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_list: *mut *mut RustHashList,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let resource_type_str = string_from_c_str(resource_type, "resource_type")?;
+        check_not_null(partition_manager, "partition_manager")?;
+        check_not_null(out_list, "out_list")?;
+
+        logger.info(format!(
+            "Scanning Rpkg files for all {} Resources.",
+            resource_type_str
+        ));
+
+        let partition_manager_ref = unsafe { &*partition_manager };
+        let resources = RpkgExtraction::get_all_resource_hash_values_by_type_from_rpkg_files(
+            partition_manager_ref,
+            resource_type_str,
+            &logger,
+        );
+        unsafe {
+            *out_list = create_hash_list(resources);
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_resource_types_from_rpkg_files(
+    partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_list: *mut *mut RustStringList,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        check_not_null(partition_manager, "partition_manager")?;
+        check_not_null(out_list, "out_list")?;
+
+        let partition_manager_ref = unsafe { &*partition_manager };
+        let resource_types = ResourceType::mounted_types(partition_manager_ref);
+        logger.debug(format!(
+            "Found {} resource types in Rpkg files.",
+            resource_types.len()
+        ));
+        unsafe {
+            *out_list = create_string_list(resource_types.iter().cloned().collect());
+        }
+        Ok(())
+    })())
 }
 
 #[repr(C)]
-pub struct RustStringList {
-    entries: *mut *mut c_char,
-    length: usize,
+pub struct NavKitResourceQuery {
+    resource_types: *const *const c_char,
+    resource_types_len: usize,
+    // Null matches every path, including unknown ones.
+    path_pattern: *const c_char,
+    path_pattern_is_regex: bool,
+    partitions: *const *const c_char,
+    partitions_len: usize,
+    min_size: u64,
+    max_size: u64,
 }
 
-pub fn create_string_list(strings: Vec<String>) -> *mut RustStringList {
-    let rust_strings = strings
-        .into_iter()
-        .filter_map(|s| CString::new(s).ok())
-        .map(|c_string| c_string.into_raw())
-        .collect::<Vec<*mut c_char>>();
+#[no_mangle]
+pub extern "C" fn get_default_resource_query() -> NavKitResourceQuery {
+    let defaults = ResourceQuery::default();
+    NavKitResourceQuery {
+        resource_types: std::ptr::null(),
+        resource_types_len: 0,
+        path_pattern: std::ptr::null(),
+        path_pattern_is_regex: false,
+        partitions: std::ptr::null(),
+        partitions_len: 0,
+        min_size: defaults.min_size,
+        max_size: defaults.max_size,
+    }
+}
 
-    let length = rust_strings.len();
-    let entries = rust_strings.leak().as_mut_ptr();
+fn resource_query_from_c(query: *const NavKitResourceQuery) -> ResourceQuery {
+    match unsafe { query.as_ref() } {
+        Some(query_ref) => ResourceQuery {
+            resource_types: strings_from_c_array(
+                query_ref.resource_types,
+                query_ref.resource_types_len,
+            ),
+            path_pattern: if query_ref.path_pattern.is_null() {
+                None
+            } else {
+                let pattern = unsafe { CStr::from_ptr(query_ref.path_pattern) }
+                    .to_string_lossy()
+                    .into_owned();
+                Some(if query_ref.path_pattern_is_regex {
+                    PathPattern::Regex(pattern)
+                } else {
+                    PathPattern::Glob(pattern)
+                })
+            },
+            partitions: strings_from_c_array(query_ref.partitions, query_ref.partitions_len),
+            min_size: query_ref.min_size,
+            max_size: query_ref.max_size,
+        },
+        None => ResourceQuery::default(),
+    }
+}
 
-    let list = Box::new(RustStringList { entries, length });
-    Box::into_raw(list)
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn search_resources_in_rpkg_files(
+    partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
+    query: *const NavKitResourceQuery,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_result: *mut *mut ResourceSearchResult,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        check_not_null(partition_manager, "partition_manager")?;
+        check_not_null(out_result, "out_result")?;
+
+        let partition_manager_ref = unsafe { &*partition_manager };
+        let search_result = ResourceSearch::search(
+            partition_manager_ref,
+            &resource_query_from_c(query),
+            &logger,
+        )?;
+        unsafe {
+            *out_result = Box::into_raw(Box::new(search_result));
+        }
+        Ok(())
+    })())
+}
+
+#[cfg(feature = "path-list")]
+fn registered_hash_list() -> Result<std::sync::Arc<HashList>, NavKitError> {
+    HashList::registered().ok_or_else(|| {
+        NavKitError::new(
+            NavKitErrorCode::HashListNotLoaded,
+            "No hash list is loaded. Call load_hash_list first",
+        )
+    })
 }
 
+#[cfg(feature = "path-list")]
 #[no_mangle]
-pub extern "C" fn get_string_from_list(list: *mut RustStringList, index: usize) -> *const c_char {
-    if list.is_null() {
-        return std::ptr::null();
+pub extern "C" fn load_hash_list(
+    hash_list_path: *const c_char,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let hash_list_path_str = string_from_c_str(hash_list_path, "hash_list_path")?;
+
+        HashList::register(HashList::load(Path::new(&hash_list_path_str), &logger)?);
+        Ok(())
+    })())
+}
+
+#[cfg(feature = "path-list")]
+#[no_mangle]
+pub extern "C" fn unload_hash_list() {
+    HashList::unregister();
+}
+
+#[cfg(feature = "path-list")]
+#[no_mangle]
+pub extern "C" fn lookup_resource_path(
+    hash: *const c_char,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let hash_str = string_from_c_str(hash, "hash")?;
+        let rrid = ResourceHash::parse(&hash_str)?;
+        let hash_list = registered_hash_list()?;
+        let path = hash_list.path(&rrid).ok_or_else(|| {
+            NavKitError::new(
+                NavKitErrorCode::UnknownHash,
+                format!("No path is known for hash {}", hash_str),
+            )
+        })?;
+        write_string_to_buffer(path, buffer, buffer_len, out_len)
+    })())
+}
+
+#[cfg(feature = "path-list")]
+#[no_mangle]
+pub extern "C" fn lookup_resource_type(
+    hash: *const c_char,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let hash_str = string_from_c_str(hash, "hash")?;
+        let rrid = ResourceHash::parse(&hash_str)?;
+        let hash_list = registered_hash_list()?;
+        let entry = hash_list.entry(&rrid).ok_or_else(|| {
+            NavKitError::new(
+                NavKitErrorCode::UnknownHash,
+                format!("Hash {} is not in the hash list", hash_str),
+            )
+        })?;
+        write_string_to_buffer(&entry.resource_type, buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[cfg(feature = "path-list")]
+#[no_mangle]
+pub extern "C" fn lookup_resource_hash(
+    resource_path: *const c_char,
+    out_hash: *mut u64,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let resource_path_str = string_from_c_str(resource_path, "resource_path")?;
+        check_not_null(out_hash, "out_hash")?;
+        let hash_list = registered_hash_list()?;
+        let rrid = hash_list.hash(&resource_path_str).ok_or_else(|| {
+            NavKitError::new(
+                NavKitErrorCode::UnknownHash,
+                format!("Path {} is not in the hash list", resource_path_str),
+            )
+        })?;
+        unsafe {
+            *out_hash = ResourceHash::to_u64(&rrid);
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_resource_path_hash(
+    resource_path: *const c_char,
+    out_hash: *mut u64,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let resource_path_str = string_from_c_str(resource_path, "resource_path")?;
+        check_not_null(out_hash, "out_hash")?;
+
+        let rrid = ResourceHash::from_resource_path(&resource_path_str)?;
+        unsafe {
+            *out_hash = ResourceHash::to_u64(&rrid);
+        }
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn normalize_resource_path(
+    resource_path: *const c_char,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let resource_path_str = string_from_c_str(resource_path, "resource_path")?;
+        let normalized_path = ResourceHash::normalize_resource_path(&resource_path_str)?;
+        write_string_to_buffer(&normalized_path, buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn read_resource_from_rpkg(
+    runtime_folder: *const c_char,
+    hash: *const c_char,
+    partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    buffer: *mut u8,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let runtime_folder_str = string_from_c_str(runtime_folder, "runtime_folder")?;
+        let hash_str = string_from_c_str(hash, "hash")?;
+        check_not_null(partition_manager, "partition_manager")?;
+
+        let partition_manager_ref = unsafe { &*partition_manager };
+        if buffer.is_null() {
+            let rrid = ResourceHash::parse(&hash_str)?;
+            let resource_info = PackageScan::get_resource_info(partition_manager_ref, &rrid)
+                .ok_or_else(|| {
+                    NavKitError::new(
+                        NavKitErrorCode::UnknownHash,
+                        format!("Error getting resource info for hash: {}", hash_str),
+                    )
+                })?;
+            check_not_null(out_len, "out_len")?;
+            unsafe {
+                *out_len = resource_info.last_occurrence.size() as usize;
+            }
+            return Ok(());
+        }
+        let resource_contents = RpkgExtraction::read_resource_from_rpkg(
+            runtime_folder_str,
+            &hash_str,
+            partition_manager_ref,
+            &logger,
+        )?;
+        write_bytes_to_buffer(&resource_contents, buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn read_resource_blob_from_rpkg(
+    runtime_folder: *const c_char,
+    hash: *const c_char,
+    partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_blob: *mut *mut RustBlob,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let runtime_folder_str = string_from_c_str(runtime_folder, "runtime_folder")?;
+        let hash_str = string_from_c_str(hash, "hash")?;
+        check_not_null(partition_manager, "partition_manager")?;
+        check_not_null(out_blob, "out_blob")?;
+
+        let partition_manager_ref = unsafe { &*partition_manager };
+        let resource_contents = RpkgExtraction::read_resource_from_rpkg(
+            runtime_folder_str,
+            &hash_str,
+            partition_manager_ref,
+            &logger,
+        )?;
+        unsafe {
+            *out_blob = create_blob(resource_contents);
+        }
+        Ok(())
+    })())
+}
+
+#[repr(C)]
+pub struct EntityTransform {
+    position: Vec3,
+    rotation: Rotation,
+    scale: Vec3,
+}
+
+fn write_string_to_buffer(
+    value: &str,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> Result<(), NavKitError> {
+    let bytes = value.as_bytes();
+    if !out_len.is_null() {
+        unsafe {
+            *out_len = bytes.len();
+        }
+    }
+    if buffer.is_null() {
+        return Ok(());
+    }
+    if buffer_len < bytes.len() + 1 {
+        return Err(NavKitError::new(
+            NavKitErrorCode::BufferTooSmall,
+            format!(
+                "Buffer of {} bytes is too small for a string of {} bytes",
+                buffer_len,
+                bytes.len()
+            ),
+        ));
     }
     unsafe {
-        let list_ref = &*list;
-        if index >= list_ref.length {
-            return std::ptr::null();
-        }
-        // Access the pointer at the given index
-        *list_ref.entries.add(index)
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer as *mut u8, bytes.len());
+        *buffer.add(bytes.len()) = 0;
     }
+    Ok(())
 }
 
-#[no_mangle]
-pub extern "C" fn free_entities_json(ptr: *mut EntitiesJson) {
-    if ptr.is_null() {
-        return;
+fn write_bytes_to_buffer(
+    bytes: &[u8],
+    buffer: *mut u8,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> Result<(), NavKitError> {
+    if !out_len.is_null() {
+        unsafe {
+            *out_len = bytes.len();
+        }
+    }
+    if buffer.is_null() {
+        return Ok(());
+    }
+    if buffer_len < bytes.len() {
+        return Err(NavKitError::new(
+            NavKitErrorCode::BufferTooSmall,
+            format!(
+                "Buffer of {} bytes is too small for {} bytes of data",
+                buffer_len,
+                bytes.len()
+            ),
+        ));
     }
     unsafe {
-        let _ = Box::from_raw(ptr);
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, bytes.len());
     }
+    Ok(())
+}
+
+fn element_at<'a, T>(elements: &'a [T], index: usize, kind: &str) -> Result<&'a T, NavKitError> {
+    elements.get(index).ok_or_else(|| {
+        NavKitError::new(
+            NavKitErrorCode::IndexOutOfRange,
+            format!(
+                "{} index {} is out of range for {} entries",
+                kind,
+                index,
+                elements.len()
+            ),
+        )
+    })
+}
+
+fn entities_json_mesh<'a>(
+    entities_json: *const EntitiesJson,
+    index: usize,
+) -> Result<&'a MeshHashesAndEntity, NavKitError> {
+    check_not_null(entities_json, "entities_json")?;
+    element_at(&unsafe { &*entities_json }.meshes, index, "Mesh")
+}
+
+fn entities_json_pf_box<'a>(
+    entities_json: *const EntitiesJson,
+    index: usize,
+) -> Result<&'a PfBox, NavKitError> {
+    check_not_null(entities_json, "entities_json")?;
+    element_at(&unsafe { &*entities_json }.pf_boxes, index, "PfBox")
+}
+
+fn entities_json_pf_seed_point<'a>(
+    entities_json: *const EntitiesJson,
+    index: usize,
+) -> Result<&'a PfSeedPoint, NavKitError> {
+    check_not_null(entities_json, "entities_json")?;
+    element_at(
+        &unsafe { &*entities_json }.pf_seed_points,
+        index,
+        "PfSeedPoint",
+    )
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
-pub extern "C" fn free_hashset_string(ptr: *mut HashSet<String>) {
+pub extern "C" fn load_entities_json(
+    nav_json_file: *const c_char,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_entities_json: *mut *mut EntitiesJson,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let nav_json_file_str = string_from_c_str(nav_json_file, "nav_json_file")?;
+        check_not_null(out_entities_json, "out_entities_json")?;
+
+        let entities_json = EntitiesJson::build_from_nav_json_file(nav_json_file_str, &logger)?;
+        unsafe {
+            *out_entities_json = Box::into_raw(Box::new(entities_json));
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn load_entities_json_from_string(
+    nav_json_string: *const c_char,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_entities_json: *mut *mut EntitiesJson,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let nav_json_str = string_from_c_str(nav_json_string, "nav_json_string")?;
+        check_not_null(out_entities_json, "out_entities_json")?;
+
+        let entities_json = EntitiesJson::build_from_nav_json_string(nav_json_str, &logger)?;
+        unsafe {
+            *out_entities_json = Box::into_raw(Box::new(entities_json));
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_count(entities_json: *const EntitiesJson) -> usize {
+    match unsafe { entities_json.as_ref() } {
+        Some(json) => json.meshes.len(),
+        None => 0,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_aloc_hash(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let mesh = entities_json_mesh(entities_json, index)?;
+        write_string_to_buffer(&mesh.aloc_hash.to_hex_string(), buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_aloc_hash_value(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_hash: *mut u64,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let mesh = entities_json_mesh(entities_json, index)?;
+        check_not_null(out_hash, "out_hash")?;
+        unsafe {
+            *out_hash = ResourceHash::to_u64(&mesh.aloc_hash);
+        }
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_prim_hash(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let mesh = entities_json_mesh(entities_json, index)?;
+        write_string_to_buffer(&mesh.prim_hash.to_hex_string(), buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_prim_hash_value(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_hash: *mut u64,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let mesh = entities_json_mesh(entities_json, index)?;
+        check_not_null(out_hash, "out_hash")?;
+        unsafe {
+            *out_hash = ResourceHash::to_u64(&mesh.prim_hash);
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_entity_id(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_id: *mut u64,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let mesh = entities_json_mesh(entities_json, index)?;
+        check_not_null(out_id, "out_id")?;
+        unsafe {
+            *out_id = mesh.entity.id;
+        }
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_entity_name(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let mesh = entities_json_mesh(entities_json, index)?;
+        let name = mesh.entity.name.as_deref().unwrap_or_default();
+        write_string_to_buffer(name, buffer, buffer_len, out_len)
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_entity_tblu(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let mesh = entities_json_mesh(entities_json, index)?;
+        let tblu = mesh.entity.tblu.as_deref().unwrap_or_default();
+        write_string_to_buffer(tblu, buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_transform(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_transform: *mut EntityTransform,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let mesh = entities_json_mesh(entities_json, index)?;
+        check_not_null(out_transform, "out_transform")?;
+        unsafe {
+            *out_transform = EntityTransform {
+                position: mesh.entity.position,
+                rotation: mesh.entity.rotation,
+                scale: mesh.entity.scale.data,
+            };
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_scale_type(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_scale_type: *mut ScaleKind,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let mesh = entities_json_mesh(entities_json, index)?;
+        check_not_null(out_scale_type, "out_scale_type")?;
+        unsafe {
+            *out_scale_type = mesh.entity.scale.r#type.kind();
+        }
+        Ok(())
+    })())
+}
+
+// Writes the scale type as it appears in the nav.json file, including unknown types.
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_scale_type_name(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let mesh = entities_json_mesh(entities_json, index)?;
+        write_string_to_buffer(mesh.entity.scale.r#type.name(), buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_pf_box_count(entities_json: *const EntitiesJson) -> usize {
+    match unsafe { entities_json.as_ref() } {
+        Some(json) => json.pf_boxes.len(),
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_pf_box_id(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_id: *mut u64,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let pf_box = entities_json_pf_box(entities_json, index)?;
+        check_not_null(out_id, "out_id")?;
+        unsafe {
+            *out_id = pf_box.id;
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_pf_box_type(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_pf_box_type: *mut PfBoxKind,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let pf_box = entities_json_pf_box(entities_json, index)?;
+        check_not_null(out_pf_box_type, "out_pf_box_type")?;
+        unsafe {
+            *out_pf_box_type = pf_box.r#type.data.kind();
+        }
+        Ok(())
+    })())
+}
+
+// Writes the box type as it appears in the nav.json file, including unknown types.
+#[no_mangle]
+pub extern "C" fn get_entities_json_pf_box_type_name(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let pf_box = entities_json_pf_box(entities_json, index)?;
+        write_string_to_buffer(pf_box.r#type.data.name(), buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_pf_box_transform(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_transform: *mut EntityTransform,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let pf_box = entities_json_pf_box(entities_json, index)?;
+        check_not_null(out_transform, "out_transform")?;
+        unsafe {
+            *out_transform = EntityTransform {
+                position: pf_box.position,
+                rotation: pf_box.rotation,
+                scale: pf_box.scale.data,
+            };
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_pf_seed_point_count(
+    entities_json: *const EntitiesJson,
+) -> usize {
+    match unsafe { entities_json.as_ref() } {
+        Some(json) => json.pf_seed_points.len(),
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_pf_seed_point_id(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_id: *mut u64,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let pf_seed_point = entities_json_pf_seed_point(entities_json, index)?;
+        check_not_null(out_id, "out_id")?;
+        unsafe {
+            *out_id = pf_seed_point.id;
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_pf_seed_point_transform(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_transform: *mut EntityTransform,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let pf_seed_point = entities_json_pf_seed_point(entities_json, index)?;
+        check_not_null(out_transform, "out_transform")?;
+        unsafe {
+            *out_transform = EntityTransform {
+                position: pf_seed_point.position,
+                rotation: pf_seed_point.rotation,
+                scale: Vec3 {
+                    x: 1.0,
+                    y: 1.0,
+                    z: 1.0,
+                },
+            };
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_world_matrix(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_matrix: *mut Matrix4,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let mesh = entities_json_mesh(entities_json, index)?;
+        check_not_null(out_matrix, "out_matrix")?;
+        unsafe {
+            *out_matrix = SceneBounds::mesh_world_matrix(&mesh.entity);
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_pf_box_world_matrix(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_matrix: *mut Matrix4,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let pf_box = entities_json_pf_box(entities_json, index)?;
+        check_not_null(out_matrix, "out_matrix")?;
+        unsafe {
+            *out_matrix = SceneBounds::pf_box_world_matrix(pf_box);
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_pf_box_oriented_bounding_box(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_box: *mut OrientedBoundingBox,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let pf_box = entities_json_pf_box(entities_json, index)?;
+        check_not_null(out_box, "out_box")?;
+        unsafe {
+            *out_box = OrientedBoundingBox::from_pf_box(pf_box);
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_pf_seed_point_world_matrix(
+    entities_json: *const EntitiesJson,
+    index: usize,
+    out_matrix: *mut Matrix4,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let pf_seed_point = entities_json_pf_seed_point(entities_json, index)?;
+        check_not_null(out_matrix, "out_matrix")?;
+        unsafe {
+            *out_matrix = SceneBounds::pf_seed_point_world_matrix(pf_seed_point);
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_bounds(
+    entities_json: *const EntitiesJson,
+    out_bounds: *mut BoundingBox,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        check_not_null(entities_json, "entities_json")?;
+        check_not_null(out_bounds, "out_bounds")?;
+        unsafe {
+            *out_bounds = SceneBounds::compute(&*entities_json);
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn transform_point(
+    matrix: *const Matrix4,
+    point: Vec3,
+    out_point: *mut Vec3,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        check_not_null(matrix, "matrix")?;
+        check_not_null(out_point, "out_point")?;
+        unsafe {
+            *out_point = (*matrix).transform_point(&point);
+        }
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn get_default_mesh_filter_options() -> MeshFilterOptions {
+    MeshFilterOptions::default()
+}
+
+// Fills `out_statuses` with one status per mesh, in scene order. It must hold at least
+// `get_entities_json_mesh_count` elements. Null `mesh_filter_options` uses the defaults. Mesh
+// bounds are read from the headers of their PRIM resources, and meshes whose PRIM cannot be
+// read are kept.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_entities_json_mesh_filter_statuses(
+    entities_json: *const EntitiesJson,
+    runtime_directory: *const c_char,
+    partition_manager: *const rpkg_rs::resource::partition_manager::PartitionManager,
+    mesh_filter_options: *const MeshFilterOptions,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_statuses: *mut MeshFilterStatus,
+    out_statuses_len: usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        check_not_null(entities_json, "entities_json")?;
+        let runtime_directory_ref = string_from_c_str(runtime_directory, "runtime_directory")?;
+        check_not_null(partition_manager, "partition_manager")?;
+        check_not_null(out_statuses, "out_statuses")?;
+
+        let entities_json_ref = unsafe { &*entities_json };
+        let options = unsafe { mesh_filter_options.as_ref() }
+            .copied()
+            .unwrap_or_default();
+        let prim_bounds = MeshFilter::read_prim_bounds(
+            entities_json_ref,
+            &runtime_directory_ref,
+            unsafe { &*partition_manager },
+            &logger,
+        );
+        let statuses = MeshFilter::classify(entities_json_ref, &prim_bounds, &options);
+        if out_statuses_len < statuses.len() {
+            return Err(NavKitError::new(
+                NavKitErrorCode::BufferTooSmall,
+                format!(
+                    "Status buffer holds {} elements but the scene has {} meshes",
+                    out_statuses_len,
+                    statuses.len()
+                ),
+            ));
+        }
+        unsafe {
+            std::ptr::copy_nonoverlapping(statuses.as_ptr(), out_statuses, statuses.len());
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn diff_entities_json(
+    old_entities_json: *const EntitiesJson,
+    new_entities_json: *const EntitiesJson,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_scene_diff: *mut *mut SceneDiff,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        check_not_null(old_entities_json, "old_entities_json")?;
+        check_not_null(new_entities_json, "new_entities_json")?;
+        check_not_null(out_scene_diff, "out_scene_diff")?;
+
+        let scene_diff = SceneDiff::compare(
+            unsafe { &*old_entities_json },
+            unsafe { &*new_entities_json },
+            &logger,
+        );
+        unsafe {
+            *out_scene_diff = Box::into_raw(Box::new(scene_diff));
+        }
+        Ok(())
+    })())
+}
+
+#[repr(C)]
+pub struct NavKitSceneChange {
+    entity_kind: SceneEntityKind,
+    change_kind: SceneChangeKind,
+    id: u64,
+    // Index into the old scene's meshes, pathfinding boxes or seed points. `SIZE_MAX` for added
+    // entities.
+    old_index: usize,
+    // Index into the new scene's arrays. `SIZE_MAX` for removed entities.
+    new_index: usize,
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_scene_diff_change_count(scene_diff: *const SceneDiff) -> usize {
+    match unsafe { scene_diff.as_ref() } {
+        Some(diff) => diff.changes.len(),
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_scene_diff_kind_count(
+    scene_diff: *const SceneDiff,
+    entity_kind: SceneEntityKind,
+    change_kind: SceneChangeKind,
+) -> usize {
+    match unsafe { scene_diff.as_ref() } {
+        Some(diff) => diff.count(entity_kind, change_kind),
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_scene_diff_change(
+    scene_diff: *const SceneDiff,
+    index: usize,
+    out_change: *mut NavKitSceneChange,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        check_not_null(scene_diff, "scene_diff")?;
+        check_not_null(out_change, "out_change")?;
+        let change = element_at(&unsafe { &*scene_diff }.changes, index, "Scene change")?;
+        unsafe {
+            *out_change = NavKitSceneChange {
+                entity_kind: change.entity_kind,
+                change_kind: change.change_kind,
+                id: change.id,
+                old_index: change.old_index.unwrap_or(usize::MAX),
+                new_index: change.new_index.unwrap_or(usize::MAX),
+            };
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_scene_diff_new_aloc_hashes(
+    scene_diff: *const SceneDiff,
+    out_list: *mut *mut RustStringList,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        check_not_null(scene_diff, "scene_diff")?;
+        check_not_null(out_list, "out_list")?;
+        let hashes = unsafe { &*scene_diff }
+            .new_aloc_hashes
+            .iter()
+            .map(|hash| hash.to_hex_string())
+            .collect();
+        unsafe {
+            *out_list = create_string_list(hashes);
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_scene_diff_new_prim_hashes(
+    scene_diff: *const SceneDiff,
+    out_list: *mut *mut RustStringList,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        check_not_null(scene_diff, "scene_diff")?;
+        check_not_null(out_list, "out_list")?;
+        let hashes = unsafe { &*scene_diff }
+            .new_prim_hashes
+            .iter()
+            .map(|hash| hash.to_hex_string())
+            .collect();
+        unsafe {
+            *out_list = create_string_list(hashes);
+        }
+        Ok(())
+    })())
+}
+
+fn store_validation_report(
+    validation_report: ValidationReport,
+    out_report: *mut *mut ValidationReport,
+) {
+    unsafe {
+        *out_report = Box::into_raw(Box::new(validation_report));
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn validate_entities_json(
+    entities_json: *const EntitiesJson,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_report: *mut *mut ValidationReport,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        check_not_null(entities_json, "entities_json")?;
+        check_not_null(out_report, "out_report")?;
+
+        let entities_json_ref = unsafe { &*entities_json };
+        store_validation_report(
+            SceneValidation::validate(entities_json_ref, &logger),
+            out_report,
+        );
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn validate_nav_json_file(
+    nav_json_file: *const c_char,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_report: *mut *mut ValidationReport,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let nav_json_file_str = string_from_c_str(nav_json_file, "nav_json_file")?;
+        check_not_null(out_report, "out_report")?;
+
+        store_validation_report(
+            SceneValidation::validate_nav_json_file(nav_json_file_str, &logger)?,
+            out_report,
+        );
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn validate_nav_json_string(
+    nav_json_string: *const c_char,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_report: *mut *mut ValidationReport,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let nav_json_str = string_from_c_str(nav_json_string, "nav_json_string")?;
+        check_not_null(out_report, "out_report")?;
+
+        store_validation_report(
+            SceneValidation::validate_nav_json_string(nav_json_str, &logger),
+            out_report,
+        );
+        Ok(())
+    })())
+}
+
+fn validation_issue<'a>(
+    validation_report: *const ValidationReport,
+    index: usize,
+) -> Result<&'a ValidationIssue, NavKitError> {
+    check_not_null(validation_report, "validation_report")?;
+    element_at(
+        &unsafe { &*validation_report }.issues,
+        index,
+        "Validation issue",
+    )
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_validation_report_issue_count(
+    validation_report: *const ValidationReport,
+) -> usize {
+    match unsafe { validation_report.as_ref() } {
+        Some(report) => report.issues.len(),
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_validation_report_severity_count(
+    validation_report: *const ValidationReport,
+    severity: ValidationSeverity,
+) -> usize {
+    match unsafe { validation_report.as_ref() } {
+        Some(report) => report.count(severity),
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_validation_issue_severity(
+    validation_report: *const ValidationReport,
+    index: usize,
+    out_severity: *mut ValidationSeverity,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let issue = validation_issue(validation_report, index)?;
+        check_not_null(out_severity, "out_severity")?;
+        unsafe {
+            *out_severity = issue.severity;
+        }
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn get_validation_issue_path(
+    validation_report: *const ValidationReport,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let issue = validation_issue(validation_report, index)?;
+        write_string_to_buffer(&issue.path, buffer, buffer_len, out_len)
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn get_validation_issue_message(
+    validation_report: *const ValidationReport,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let issue = validation_issue(validation_report, index)?;
+        write_string_to_buffer(&issue.message, buffer, buffer_len, out_len)
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn create_cancellation_token() -> *mut CancellationToken {
+    Box::into_raw(Box::new(CancellationToken::new()))
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn cancel_cancellation_token(token: *const CancellationToken) {
+    if let Some(token_ref) = unsafe { token.as_ref() } {
+        token_ref.cancel();
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn reset_cancellation_token(token: *const CancellationToken) {
+    if let Some(token_ref) = unsafe { token.as_ref() } {
+        token_ref.reset();
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn is_cancellation_token_cancelled(token: *const CancellationToken) -> bool {
+    match unsafe { token.as_ref() } {
+        Some(token_ref) => token_ref.is_cancelled(),
+        None => false,
+    }
+}
+
+#[repr(C)]
+pub struct RustStringList {
+    entries: *mut *mut c_char,
+    length: usize,
+}
+
+pub fn create_string_list(strings: Vec<String>) -> *mut RustStringList {
+    let rust_strings = strings
+        .into_iter()
+        .map(|s| CString::new(s.replace('\0', "")).unwrap().into_raw())
+        .collect::<Box<[*mut c_char]>>();
+
+    let length = rust_strings.len();
+    let entries = Box::into_raw(rust_strings) as *mut *mut c_char;
+
+    let list = Box::new(RustStringList { entries, length });
+    Box::into_raw(list)
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_string_list_length(list: *const RustStringList) -> usize {
+    match unsafe { list.as_ref() } {
+        Some(list_ref) => list_ref.length,
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_string_from_list(list: *mut RustStringList, index: usize) -> *const c_char {
+    if list.is_null() {
+        return std::ptr::null();
+    }
+    unsafe {
+        let list_ref = &*list;
+        if index >= list_ref.length {
+            return std::ptr::null();
+        }
+        // Access the pointer at the given index
+        *list_ref.entries.add(index)
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn free_string_list(list: *mut RustStringList) {
+    if list.is_null() {
+        return;
+    }
+    unsafe {
+        let list = Box::from_raw(list);
+        let entries = Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+            list.entries,
+            list.length,
+        ));
+        for entry in entries.iter() {
+            let _ = CString::from_raw(*entry);
+        }
+    }
+}
+
+#[repr(C)]
+pub struct RustHashList {
+    entries: *mut u64,
+    length: usize,
+}
+
+pub fn create_hash_list(hashes: Vec<u64>) -> *mut RustHashList {
+    let hashes = hashes.into_boxed_slice();
+    let length = hashes.len();
+    let entries = Box::into_raw(hashes) as *mut u64;
+
+    let list = Box::new(RustHashList { entries, length });
+    Box::into_raw(list)
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_hash_list_length(list: *const RustHashList) -> usize {
+    match unsafe { list.as_ref() } {
+        Some(list_ref) => list_ref.length,
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_hash_list_data(list: *const RustHashList) -> *const u64 {
+    match unsafe { list.as_ref() } {
+        Some(list_ref) => list_ref.entries,
+        None => std::ptr::null(),
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_hash_from_list(list: *const RustHashList, index: usize) -> u64 {
+    match unsafe { list.as_ref() } {
+        Some(list_ref) if index < list_ref.length => unsafe { *list_ref.entries.add(index) },
+        _ => ResourceHash::INVALID,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn free_hash_list(list: *mut RustHashList) {
+    if list.is_null() {
+        return;
+    }
+    unsafe {
+        let list = Box::from_raw(list);
+        let _ = Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+            list.entries,
+            list.length,
+        ));
+    }
+}
+
+#[repr(C)]
+pub struct RustBlob {
+    data: *mut u8,
+    length: usize,
+}
+
+pub fn create_blob(bytes: Vec<u8>) -> *mut RustBlob {
+    let bytes = bytes.into_boxed_slice();
+    let length = bytes.len();
+    let data = Box::into_raw(bytes) as *mut u8;
+
+    let blob = Box::new(RustBlob { data, length });
+    Box::into_raw(blob)
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_blob_length(blob: *const RustBlob) -> usize {
+    match unsafe { blob.as_ref() } {
+        Some(blob_ref) => blob_ref.length,
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_blob_data(blob: *const RustBlob) -> *const u8 {
+    match unsafe { blob.as_ref() } {
+        Some(blob_ref) => blob_ref.data,
+        None => std::ptr::null(),
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn free_blob(blob: *mut RustBlob) {
+    if blob.is_null() {
+        return;
+    }
+    unsafe {
+        let blob = Box::from_raw(blob);
+        let _ = Box::from_raw(std::ptr::slice_from_raw_parts_mut(blob.data, blob.length));
+    }
+}
+
+fn extraction_outcome<'a>(
+    extraction_result: *const ExtractionResult,
+    index: usize,
+) -> Result<&'a ExtractionOutcome, NavKitError> {
+    check_not_null(extraction_result, "extraction_result")?;
+    element_at(
+        &unsafe { &*extraction_result }.outcomes,
+        index,
+        "Extraction result",
+    )
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_extraction_result_count(extraction_result: *const ExtractionResult) -> usize {
+    match unsafe { extraction_result.as_ref() } {
+        Some(result) => result.outcomes.len(),
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_extraction_result_status_count(
+    extraction_result: *const ExtractionResult,
+    status: ExtractionStatus,
+) -> usize {
+    match unsafe { extraction_result.as_ref() } {
+        Some(result) => result.count(status),
+        None => 0,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn get_extraction_result_hash(
+    extraction_result: *const ExtractionResult,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let outcome = extraction_outcome(extraction_result, index)?;
+        write_string_to_buffer(&outcome.hash, buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_extraction_result_status(
+    extraction_result: *const ExtractionResult,
+    index: usize,
+    out_status: *mut ExtractionStatus,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let outcome = extraction_outcome(extraction_result, index)?;
+        check_not_null(out_status, "out_status")?;
+        unsafe {
+            *out_status = outcome.status;
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_extraction_result_error_code(
+    extraction_result: *const ExtractionResult,
+    index: usize,
+    out_code: *mut NavKitErrorCode,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let outcome = extraction_outcome(extraction_result, index)?;
+        check_not_null(out_code, "out_code")?;
+        unsafe {
+            *out_code = outcome.error_code();
+        }
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn get_extraction_result_error_message(
+    extraction_result: *const ExtractionResult,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let outcome = extraction_outcome(extraction_result, index)?;
+        let message = match &outcome.error {
+            Some(error) => error.message.as_str(),
+            None => "",
+        };
+        write_string_to_buffer(message, buffer, buffer_len, out_len)
+    })())
+}
+
+fn resource_bundle_entry<'a>(
+    resource_bundle: *const ResourceBundle,
+    index: usize,
+) -> Result<&'a BundleEntry, NavKitError> {
+    check_not_null(resource_bundle, "resource_bundle")?;
+    element_at(
+        unsafe { &*resource_bundle }.entries(),
+        index,
+        "Bundle entry",
+    )
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn open_resource_bundle(
+    bundle_path: *const c_char,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_resource_bundle: *mut *mut ResourceBundle,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let bundle_path_str = string_from_c_str(bundle_path, "bundle_path")?;
+        check_not_null(out_resource_bundle, "out_resource_bundle")?;
+
+        let resource_bundle = ResourceBundle::open(Path::new(&bundle_path_str), &logger)?;
+        unsafe {
+            *out_resource_bundle = Box::into_raw(Box::new(resource_bundle));
+        }
+        Ok(())
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_resource_bundle_entry_count(resource_bundle: *const ResourceBundle) -> usize {
+    match unsafe { resource_bundle.as_ref() } {
+        Some(bundle) => bundle.entries().len(),
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_resource_bundle_entry_hash(
+    resource_bundle: *const ResourceBundle,
+    index: usize,
+    out_hash: *mut u64,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let entry = resource_bundle_entry(resource_bundle, index)?;
+        check_not_null(out_hash, "out_hash")?;
+        unsafe {
+            *out_hash = entry.hash;
+        }
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn get_resource_bundle_entry_type(
+    resource_bundle: *const ResourceBundle,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let entry = resource_bundle_entry(resource_bundle, index)?;
+        write_string_to_buffer(&entry.resource_type, buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn read_resource_from_bundle(
+    resource_bundle: *const ResourceBundle,
+    hash: *const c_char,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    buffer: *mut u8,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let hash_str = string_from_c_str(hash, "hash")?;
+        check_not_null(resource_bundle, "resource_bundle")?;
+
+        let resource_bundle_ref = unsafe { &*resource_bundle };
+        let hash_value = ResourceHash::to_u64(&ResourceHash::parse(&hash_str)?);
+        if buffer.is_null() {
+            let entry = resource_bundle_ref.entry(hash_value).ok_or_else(|| {
+                NavKitError::new(
+                    NavKitErrorCode::UnknownHash,
+                    format!("Resource {} is not in the bundle", hash_str),
+                )
+            })?;
+            check_not_null(out_len, "out_len")?;
+            unsafe {
+                *out_len = entry.size as usize;
+            }
+            return Ok(());
+        }
+        let resource_contents = resource_bundle_ref.read(hash_value, &logger)?;
+        write_bytes_to_buffer(&resource_contents, buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn read_resource_blob_from_bundle(
+    resource_bundle: *const ResourceBundle,
+    hash: *const c_char,
+    log_callback: Option<extern "C" fn(*const c_char)>,
+    out_blob: *mut *mut RustBlob,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let logger = Logger::new(log_callback, module_path!());
+        let hash_str = string_from_c_str(hash, "hash")?;
+        check_not_null(resource_bundle, "resource_bundle")?;
+        check_not_null(out_blob, "out_blob")?;
+
+        let resource_bundle_ref = unsafe { &*resource_bundle };
+        let hash_value = ResourceHash::to_u64(&ResourceHash::parse(&hash_str)?);
+        let resource_contents = resource_bundle_ref.read(hash_value, &logger)?;
+        unsafe {
+            *out_blob = create_blob(resource_contents);
+        }
+        Ok(())
+    })())
+}
+
+fn resource_search_hit<'a>(
+    search_result: *const ResourceSearchResult,
+    index: usize,
+) -> Result<&'a ResourceSearchHit, NavKitError> {
+    check_not_null(search_result, "search_result")?;
+    element_at(&unsafe { &*search_result }.hits, index, "Search hit")
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_resource_search_result_count(
+    search_result: *const ResourceSearchResult,
+) -> usize {
+    match unsafe { search_result.as_ref() } {
+        Some(result) => result.hits.len(),
+        None => 0,
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_resource_search_hit_hash(
+    search_result: *const ResourceSearchResult,
+    index: usize,
+    out_hash: *mut u64,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let hit = resource_search_hit(search_result, index)?;
+        check_not_null(out_hash, "out_hash")?;
+        unsafe {
+            *out_hash = ResourceHash::to_u64(&hit.hash);
+        }
+        Ok(())
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn get_resource_search_hit_type(
+    search_result: *const ResourceSearchResult,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let hit = resource_search_hit(search_result, index)?;
+        write_string_to_buffer(&hit.resource_type, buffer, buffer_len, out_len)
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn get_resource_search_hit_partition(
+    search_result: *const ResourceSearchResult,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let hit = resource_search_hit(search_result, index)?;
+        write_string_to_buffer(&hit.partition, buffer, buffer_len, out_len)
+    })())
+}
+
+#[no_mangle]
+pub extern "C" fn get_resource_search_hit_package(
+    search_result: *const ResourceSearchResult,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let hit = resource_search_hit(search_result, index)?;
+        write_string_to_buffer(&hit.package, buffer, buffer_len, out_len)
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn get_resource_search_hit_size(
+    search_result: *const ResourceSearchResult,
+    index: usize,
+    out_size: *mut u32,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let hit = resource_search_hit(search_result, index)?;
+        check_not_null(out_size, "out_size")?;
+        unsafe {
+            *out_size = hit.size;
+        }
+        Ok(())
+    })())
+}
+
+// Writes an empty string when no path is known.
+#[no_mangle]
+pub extern "C" fn get_resource_search_hit_path(
+    search_result: *const ResourceSearchResult,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> NavKitErrorCode {
+    to_error_code((|| {
+        let hit = resource_search_hit(search_result, index)?;
+        write_string_to_buffer(
+            hit.path.as_deref().unwrap_or_default(),
+            buffer,
+            buffer_len,
+            out_len,
+        )
+    })())
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn free_entities_json(ptr: *mut EntitiesJson) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let _ = Box::from_raw(ptr);
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn free_scene_diff(ptr: *mut SceneDiff) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let _ = Box::from_raw(ptr);
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn free_validation_report(ptr: *mut ValidationReport) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let _ = Box::from_raw(ptr);
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn free_extraction_result(ptr: *mut ExtractionResult) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let _ = Box::from_raw(ptr);
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn free_resource_bundle(ptr: *mut ResourceBundle) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let _ = Box::from_raw(ptr);
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn free_resource_search_result(ptr: *mut ResourceSearchResult) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let _ = Box::from_raw(ptr);
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn free_hashset_string(ptr: *mut HashSet<String>) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let _ = Box::from_raw(ptr);
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn free_cancellation_token(ptr: *mut CancellationToken) {
     if ptr.is_null() {
         return;
     }
@@ -222,6 +2475,7 @@ pub extern "C" fn free_hashset_string(ptr: *mut HashSet<String>) {
     }
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn free_partition_manager(
     ptr: *mut rpkg_rs::resource::partition_manager::PartitionManager,
@@ -229,6 +2483,7 @@ pub extern "C" fn free_partition_manager(
     if ptr.is_null() {
         return;
     }
+    ResourceType::forget_mounted(ptr);
     unsafe {
         let _ = Box::from_raw(ptr);
     }
@@ -0,0 +1,111 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_void};
+use std::sync::RwLock;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace = 0,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+// Exported functions spell callback types out as `Option<extern "C" fn(...)>` instead of using
+// these aliases: cbindgen lowers that form to a nullable function pointer, but copies
+// `Option<LogCallback>` into the header as an incomplete `Option` template.
+pub type LogCallback = extern "C" fn(*const c_char);
+
+pub type LevelLogCallback = extern "C" fn(LogLevel, *const c_char, *const c_char, *mut c_void);
+
+#[derive(Clone, Copy)]
+struct RegisteredLogger {
+    callback: LevelLogCallback,
+    user_data: *mut c_void,
+    min_level: LogLevel,
+}
+
+// The user data pointer is opaque to the library and only ever handed back to the host, which is
+// responsible for making it safe to use from the extraction worker threads.
+unsafe impl Send for RegisteredLogger {}
+unsafe impl Sync for RegisteredLogger {}
+
+static REGISTERED_LOGGER: RwLock<Option<RegisteredLogger>> = RwLock::new(None);
+
+#[derive(Clone, Copy)]
+pub struct Logger {
+    legacy_callback: Option<LogCallback>,
+    module: &'static str,
+}
+
+impl Logger {
+    // `module` is reported as the source of each message, so callers pass their own
+    // `module_path!()`.
+    pub fn new(legacy_callback: Option<LogCallback>, module: &'static str) -> Self {
+        Self {
+            legacy_callback,
+            module,
+        }
+    }
+
+    pub fn with_module(&self, module: &'static str) -> Self {
+        Self {
+            legacy_callback: self.legacy_callback,
+            module,
+        }
+    }
+
+    pub fn register(callback: LevelLogCallback, user_data: *mut c_void, min_level: LogLevel) {
+        *REGISTERED_LOGGER.write().unwrap() = Some(RegisteredLogger {
+            callback,
+            user_data,
+            min_level,
+        });
+    }
+
+    pub fn unregister() {
+        *REGISTERED_LOGGER.write().unwrap() = None;
+    }
+
+    pub fn log(&self, level: LogLevel, message: impl AsRef<str>) {
+        let msg = CString::new(message.as_ref().replace('\0', "")).unwrap();
+        // Copied out so the lock is released before calling into the host, which may register or
+        // unregister a logger from inside its callback.
+        let registered_logger = *REGISTERED_LOGGER.read().unwrap();
+        if let Some(registered_logger) = registered_logger {
+            if level >= registered_logger.min_level {
+                let module = CString::new(self.module).unwrap();
+                (registered_logger.callback)(
+                    level,
+                    module.as_ptr(),
+                    msg.as_ptr(),
+                    registered_logger.user_data,
+                );
+            }
+        }
+        if let Some(legacy_callback) = self.legacy_callback {
+            legacy_callback(msg.as_ptr());
+        }
+    }
+
+    pub fn trace(&self, message: impl AsRef<str>) {
+        self.log(LogLevel::Trace, message);
+    }
+
+    pub fn debug(&self, message: impl AsRef<str>) {
+        self.log(LogLevel::Debug, message);
+    }
+
+    pub fn info(&self, message: impl AsRef<str>) {
+        self.log(LogLevel::Info, message);
+    }
+
+    pub fn warn(&self, message: impl AsRef<str>) {
+        self.log(LogLevel::Warn, message);
+    }
+
+    pub fn error(&self, message: impl AsRef<str>) {
+        self.log(LogLevel::Error, message);
+    }
+}
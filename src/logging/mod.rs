@@ -0,0 +1 @@
+pub mod logger;
\ No newline at end of file
@@ -1 +1,6 @@
-pub mod package_scan;
\ No newline at end of file
+pub mod package_scan;
+pub mod resource_hash;
+pub mod resource_query;
+pub mod resource_search;
+pub mod resource_type;
+pub mod scan_options;
\ No newline at end of file
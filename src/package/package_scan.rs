@@ -1,3 +1,8 @@
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::logging::logger::Logger;
+use crate::package::scan_options::ScanOptions;
+use crate::progress::cancellation_token::CancellationToken;
+use crate::progress::progress_report::{ProgressCallback, ProgressPhase, ProgressReport};
 use itertools::Itertools;
 use rpkg_rs::misc::ini_file_system::IniFileSystem;
 use rpkg_rs::resource::partition_manager::{PartitionManager, PartitionState};
@@ -5,19 +10,25 @@ use rpkg_rs::resource::pdefs::PackageDefinitionSource;
 use rpkg_rs::resource::resource_info::ResourceInfo;
 use rpkg_rs::resource::resource_partition::PatchId;
 use rpkg_rs::resource::runtime_resource_id::RuntimeResourceID;
-use std::os::raw::c_char;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 pub struct ResourceInfoAndPartition {
     pub last_occurrence: ResourceInfo,
     pub last_partition: String,
+    pub last_patch_id: PatchId,
 }
 
 impl ResourceInfoAndPartition {
-    pub fn new(last_occurrence: ResourceInfo, last_partition: String) -> Self {
+    pub fn new(
+        last_occurrence: ResourceInfo,
+        last_partition: String,
+        last_patch_id: PatchId,
+    ) -> Self {
         Self {
             last_occurrence,
             last_partition,
+            last_patch_id,
         }
     }
 }
@@ -29,19 +40,41 @@ impl PackageScan {
     pub fn scan_packages(
         retail_folder: String,
         game_version: String,
-        log_callback: extern "C" fn(*const c_char),
-    ) -> Option<PartitionManager> {
+        logger: &Logger,
+        progress_callback: Option<ProgressCallback>,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> Result<PartitionManager, NavKitError> {
+        Self::scan_packages_with_options(
+            retail_folder,
+            game_version,
+            &ScanOptions::default(),
+            logger,
+            progress_callback,
+            cancellation_token,
+        )
+    }
+
+    pub fn scan_packages_with_options(
+        retail_folder: String,
+        game_version: String,
+        scan_options: &ScanOptions,
+        logger: &Logger,
+        progress_callback: Option<ProgressCallback>,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> Result<PartitionManager, NavKitError> {
+        let logger = logger.with_module(module_path!());
         let mut package_manager: PartitionManager;
         let retail_path = PathBuf::from(&retail_folder);
-        let thumbs_path = retail_path.join("thumbs.dat");
+        let thumbs_path = Self::resolve_path_case_insensitive(&retail_path, "thumbs.dat");
 
-        let thumbs = match IniFileSystem::from(&thumbs_path.as_path()) {
+        let thumbs = match IniFileSystem::from(thumbs_path.as_path()) {
             Ok(c) => c,
             Err(e) => {
-                let msg =
-                    std::ffi::CString::new(format!("Error reading thumbs file: {:?}", e)).unwrap();
-                log_callback(msg.as_ptr());
-                return None;
+                return Err(NavKitError::new(
+                    NavKitErrorCode::MissingThumbsDat,
+                    format!("Error reading thumbs file: {:?}", e),
+                )
+                .log(&logger));
             }
         };
 
@@ -51,100 +84,144 @@ impl PackageScan {
             app_options.options().get("PROJECT_PATH"),
             app_options.options().get("RUNTIME_PATH"),
         ) {
-            runtime_path = PathBuf::from(format!(
-                "{}\\{proj_path}\\{relative_runtime_path}",
-                retail_path.display()
-            ));
+            let project_path = Self::resolve_path_case_insensitive(&retail_path, proj_path);
+            runtime_path =
+                Self::resolve_path_case_insensitive(&project_path, relative_runtime_path);
         } else {
-            let msg = std::ffi::CString::new(
+            return Err(NavKitError::new(
+                NavKitErrorCode::InvalidThumbsDat,
                 format!("Missing required properties inside thumbs.dat:\n PROJECT_PATH: {}\n RUNTIME_PATH: {}",
                         app_options.has_option("PROJECT_PATH"),
-                        app_options.has_option("RUNTIME_PATH"))).unwrap();
-            log_callback(msg.as_ptr());
-
-            return None;
+                        app_options.has_option("RUNTIME_PATH")),
+            )
+            .log(&logger));
+        }
+        if !runtime_path.is_dir() {
+            return Err(NavKitError::new(
+                NavKitErrorCode::RuntimeFolderNotFound,
+                format!("Runtime folder does not exist: {}", runtime_path.display()),
+            )
+            .log(&logger));
         }
-        let msg = std::ffi::CString::new(format!(
+        logger.info(format!(
             "start reading package definitions {:?}",
             runtime_path
-        ))
-        .unwrap();
-        log_callback(msg.as_ptr());
+        ));
 
         package_manager = PartitionManager::new(runtime_path.clone());
 
         //read the packagedefs here
-        let mut last_index = 0;
-        let mut progress = 0.0;
-        let progress_callback = |current, state: &PartitionState| {
-            if current != last_index {
-                last_index = current;
-                print!("Mounting partition {} ", current);
-            }
-            let install_progress = (state.install_progress * 10.0).ceil() / 10.0;
-
-            let chars_to_add = (install_progress * 10.0 - progress * 10.0) as usize * 2;
-            let chars_to_add = std::cmp::min(chars_to_add, 20);
-            print!("{}", "█".repeat(chars_to_add));
-
-            progress = install_progress;
-
-            if progress == 1.0 {
-                progress = 0.0;
-                let msg = std::ffi::CString::new(" done :)").unwrap();
-                log_callback(msg.as_ptr());
+        let package_defs_bytes = match fs::read(
+            Self::resolve_path_case_insensitive(&runtime_path, "packagedefinition.txt").as_path(),
+        ) {
+            Ok(c) => c,
+            Err(e) => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::PackageDefinitionRead,
+                    format!("Error reading package definition file: {}", e),
+                )
+                .log(&logger));
             }
         };
 
-        let package_defs_bytes =
-            match std::fs::read(runtime_path.join("packagedefinition.txt").as_path()) {
-                Ok(c) => c,
-                Err(e) => {
-                    let msg = std::ffi::CString::new(format!(
-                        "Error reading package definition file: {}",
-                        e
-                    ))
-                    .unwrap();
-                    log_callback(msg.as_ptr());
-                    return None;
-                }
-            };
-
-        let mut package_defs = match match game_version.as_str() {
+        let package_defs = match match game_version.as_str() {
             "HM2016" => PackageDefinitionSource::HM2016(package_defs_bytes).read(),
             "HM2" => PackageDefinitionSource::HM2(package_defs_bytes).read(),
             "HM3" => PackageDefinitionSource::HM3(package_defs_bytes).read(),
             e => {
-                let msg = std::ffi::CString::new(format!("invalid game version: {}", e)).unwrap();
-                log_callback(msg.as_ptr());
-
-                return None;
+                return Err(NavKitError::new(
+                    NavKitErrorCode::InvalidGameVersion,
+                    format!("invalid game version: {}", e),
+                )
+                .log(&logger));
             }
         } {
             Ok(defs) => defs,
             Err(e) => {
-                let msg =
-                    std::ffi::CString::new(format!("Failed to parse package definitions {}", e))
-                        .unwrap();
-                log_callback(msg.as_ptr());
-                return None;
+                return Err(NavKitError::new(
+                    NavKitErrorCode::PackageDefinitionParse,
+                    format!("Failed to parse package definitions {}", e),
+                )
+                .log(&logger));
             }
         };
 
-        for partition in package_defs.iter_mut() {
-            partition.set_max_patch_level(301);
-        }
+        let package_defs = package_defs
+            .into_iter()
+            .filter_map(|mut partition| {
+                if !scan_options.includes_partition(&partition) {
+                    logger.debug(format!("Skipping excluded partition {}", partition.id()));
+                    return None;
+                }
+                partition.set_max_patch_level(scan_options.patch_level_for(&partition));
+                Some(partition)
+            })
+            .collect::<Vec<_>>();
 
-        if let Err(e) = package_manager.mount_partitions(
-            PackageDefinitionSource::Custom(package_defs),
+        let progress = ProgressReport::new(
             progress_callback,
-        ) {
-            let msg =
-                std::ffi::CString::new(format!("failed to init package manager: {}", e)).unwrap();
-            log_callback(msg.as_ptr());
-            return None;
-        };
-        Some(package_manager)
+            ProgressPhase::MountPartitions,
+            package_defs.len(),
+        );
+        for (index, partition) in package_defs.into_iter().enumerate() {
+            let partition_name = partition.id().to_string();
+            CancellationToken::check(cancellation_token).map_err(|e| e.log(&logger))?;
+            progress.report(index, &partition_name);
+            let mut mounted = false;
+            if let Err(e) = package_manager.mount_partition(partition, |state: &PartitionState| {
+                mounted = state.mounted;
+            }) {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::PartitionMount,
+                    format!("failed to init package manager: {}", e),
+                )
+                .log(&logger));
+            };
+            if mounted {
+                logger.debug(format!("Mounted partition {}", partition_name));
+            } else {
+                logger.warn(format!("Skipped missing partition {}", partition_name));
+            }
+            progress.report(index + 1, &partition_name);
+        }
+        Ok(package_manager)
+    }
+
+    // Joins a path read from thumbs.dat onto `base`, accepting either separator and matching each
+    // component case-insensitively so Windows installs can be scanned from case-sensitive file
+    // systems. Components that cannot be found are joined as written.
+    pub fn resolve_path_case_insensitive(base: &Path, relative_path: &str) -> PathBuf {
+        let mut resolved = base.to_path_buf();
+        for component in relative_path.split(['/', '\\']) {
+            match component {
+                "" | "." => {}
+                ".." => {
+                    resolved.pop();
+                }
+                name => {
+                    let exact = resolved.join(name);
+                    resolved = if exact.exists() {
+                        exact
+                    } else {
+                        Self::find_entry_case_insensitive(&resolved, name).unwrap_or(exact)
+                    };
+                }
+            }
+        }
+        resolved
+    }
+
+    fn find_entry_case_insensitive(directory: &Path, name: &str) -> Option<PathBuf> {
+        fs::read_dir(directory)
+            .ok()?
+            .flatten()
+            .find(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .eq_ignore_ascii_case(name)
+            })
+            .map(|entry| entry.path())
     }
 
     pub fn get_resource_info(
@@ -153,13 +230,14 @@ impl PackageScan {
     ) -> Option<ResourceInfoAndPartition> {
         let mut last_occurrence: Option<&ResourceInfo> = None;
         let mut last_partition: Option<String> = None;
+        let mut last_patch_id = PatchId::Base;
         for partition in package_manager.partitions() {
             let changes = partition.resource_patch_indices(rrid);
             let deletions = partition.resource_removal_indices(rrid);
             let occurrences = changes
                 .clone()
                 .into_iter()
-                .chain(deletions.clone().into_iter())
+                .chain(deletions.clone())
                 .collect::<Vec<PatchId>>();
             for occurrence in occurrences.iter().sorted() {
                 if deletions.contains(occurrence) {
@@ -169,10 +247,11 @@ impl PackageScan {
                     if let Ok(info) = partition.resource_info_from(rrid, *occurrence) {
                         last_occurrence = Some(info);
                         last_partition = Some(partition.partition_info().filename(*occurrence));
+                        last_patch_id = *occurrence;
                     }
                 }
             }
-            if !last_occurrence.is_none() {
+            if last_occurrence.is_some() {
                 break;
             }
         }
@@ -182,6 +261,7 @@ impl PackageScan {
         Some(ResourceInfoAndPartition::new(
             last_occurrence.unwrap().clone(),
             last_partition.unwrap(),
+            last_patch_id,
         ))
     }
 }
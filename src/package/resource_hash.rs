@@ -0,0 +1,227 @@
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+#[cfg(feature = "path-list")]
+use crate::path_list::hash_list::HashList;
+use md5::{Digest, Md5};
+use rpkg_rs::misc::resource_id::ResourceID;
+use rpkg_rs::resource::runtime_resource_id::RuntimeResourceID;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+// `RuntimeResourceID` does not expose its value, but its derived `Hash` writes exactly that
+// `u64`. Anything else written marks the capture as unusable.
+#[derive(Default)]
+struct IdCapture(Option<u64>);
+
+impl Hasher for IdCapture {
+    fn finish(&self) -> u64 {
+        self.0.unwrap_or_default()
+    }
+
+    fn write(&mut self, _bytes: &[u8]) {
+        self.0 = None;
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = Some(value);
+    }
+}
+
+pub struct ResourceHash;
+
+impl ResourceHash {
+    pub const INVALID: u64 = 0x00FFFFFFFFFFFFFF;
+
+    pub fn to_u64(rrid: &RuntimeResourceID) -> u64 {
+        let mut capture = IdCapture::default();
+        rrid.hash(&mut capture);
+        match capture.0 {
+            Some(value) => value,
+            None => u64::from_str_radix(rrid.to_hex_string().as_str(), 16).unwrap_or(Self::INVALID),
+        }
+    }
+
+    // The game hashes a normalized path to the MD5 digest bytes 1 to 7, read big-endian.
+    pub fn path_hash(path: &str) -> Result<u64, NavKitError> {
+        let normalized_path = Self::normalize_resource_path(path)?;
+        let digest = Md5::digest(normalized_path.as_bytes());
+        Ok(digest[1..8]
+            .iter()
+            .fold(0u64, |hash, byte| (hash << 8) | u64::from(*byte)))
+    }
+
+    // Resource paths look like `[assembly:/_pro/...].pc_entitytemplate`. Anything else is
+    // treated as a hex hash.
+    pub fn is_resource_path(value: &str) -> bool {
+        value.trim_start().starts_with('[')
+    }
+
+    // Accepts either a hex hash, optionally `0x` prefixed, or a resource path.
+    pub fn parse(hash: &str) -> Result<RuntimeResourceID, NavKitError> {
+        if Self::is_resource_path(hash) {
+            return Self::from_resource_path(hash);
+        }
+        RuntimeResourceID::from_hex_string(hash.trim()).map_err(|_| {
+            NavKitError::new(
+                NavKitErrorCode::InvalidHash,
+                format!("Error getting RRID from hash: {}", hash),
+            )
+        })
+    }
+
+    // The name used for output files and reports: hashes are kept as given, paths are replaced
+    // by their hash.
+    pub fn output_name(requested: &str, rrid: &RuntimeResourceID) -> String {
+        if Self::is_resource_path(requested) {
+            rrid.to_hex_string()
+        } else {
+            requested.to_string()
+        }
+    }
+
+    // Lower-cases the path, removes surrounding whitespace and control characters, uses forward
+    // slashes and adds the `pc_` platform tag to the extension, matching what the game hashes.
+    // The tag is only added to the final extension, so names such as `npc_guard` stay intact.
+    pub fn normalize_resource_path(path: &str) -> Result<String, NavKitError> {
+        let mut normalized_path = path.trim().to_ascii_lowercase().replace('\\', "/");
+        normalized_path.retain(|c| c as u32 > 0x1F);
+        let extension_start = match normalized_path.rfind('.') {
+            Some(dot) if ResourceID::from_str(&normalized_path).is_ok() => dot + 1,
+            _ => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::InvalidResourcePath,
+                    format!("Invalid resource path: {}", path),
+                ));
+            }
+        };
+        if !normalized_path[extension_start..].starts_with("pc_") {
+            normalized_path.insert_str(extension_start, "pc_");
+        }
+        Ok(normalized_path)
+    }
+
+    #[cfg(feature = "path-list")]
+    pub fn path_of(rrid: &RuntimeResourceID) -> Option<String> {
+        HashList::registered()?.path(rrid).map(str::to_string)
+    }
+
+    #[cfg(not(feature = "path-list"))]
+    pub fn path_of(_rrid: &RuntimeResourceID) -> Option<String> {
+        None
+    }
+
+    #[cfg(feature = "path-list")]
+    pub fn paths_available() -> bool {
+        HashList::registered().is_some()
+    }
+
+    #[cfg(not(feature = "path-list"))]
+    pub fn paths_available() -> bool {
+        false
+    }
+
+    // Formats a hash for log messages, adding its path when a hash list is loaded.
+    pub fn describe(rrid: &RuntimeResourceID) -> String {
+        match Self::path_of(rrid) {
+            Some(path) => format!("{} ({})", rrid.to_hex_string(), path),
+            None => rrid.to_hex_string(),
+        }
+    }
+
+    pub fn from_resource_path(path: &str) -> Result<RuntimeResourceID, NavKitError> {
+        Ok(RuntimeResourceID::from(Self::path_hash(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_IMAGE_HASH: u64 = 0x00290D5B143172A3;
+
+    #[test]
+    fn path_hash_matches_known_values() {
+        assert_eq!(
+            ResourceHash::path_hash("[assembly:/_test/lib.a?/test_image.png].pc_webp").unwrap(),
+            TEST_IMAGE_HASH
+        );
+        assert_eq!(
+            ResourceHash::to_u64(
+                &ResourceHash::from_resource_path(
+                    "[assembly:/_test/lib.a?/test_image.png].pc_webp"
+                )
+                .unwrap()
+            ),
+            TEST_IMAGE_HASH
+        );
+    }
+
+    #[test]
+    fn path_hash_normalizes_case_whitespace_separators_and_platform_tag() {
+        for path in [
+            "[ASSEMBLY:/_TEST/LIB.A?/TEST_IMAGE.PNG].PC_WEBP",
+            "  [assembly:/_test/lib.a?/test_image.png].pc_webp\t\n",
+            "[assembly:\\_test\\lib.a?\\test_image.png].pc_webp",
+            "[assembly:/_test/lib.a?/test_image.png].webp",
+        ] {
+            assert_eq!(
+                ResourceHash::path_hash(path).unwrap(),
+                TEST_IMAGE_HASH,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn normalize_resource_path_only_tags_the_final_extension() {
+        assert_eq!(
+            ResourceHash::normalize_resource_path("[assembly:/npc_guard.template?/x.entity].fx")
+                .unwrap(),
+            "[assembly:/npc_guard.template?/x.entity].pc_fx"
+        );
+        assert_eq!(
+            ResourceHash::normalize_resource_path("not a path")
+                .unwrap_err()
+                .code,
+            NavKitErrorCode::InvalidResourcePath
+        );
+    }
+
+    #[test]
+    fn to_u64_matches_the_hex_form() {
+        for value in [0u64, 1, 0x00F5817876E4B2BB, 0x00FFFFFFFFFFFFFE] {
+            let rrid = RuntimeResourceID::from(value);
+            assert_eq!(ResourceHash::to_u64(&rrid), value);
+            assert_eq!(
+                ResourceHash::to_u64(&rrid),
+                u64::from_str_radix(&rrid.to_hex_string(), 16).unwrap()
+            );
+        }
+        assert_eq!(
+            ResourceHash::to_u64(&RuntimeResourceID::invalid()),
+            ResourceHash::INVALID
+        );
+    }
+
+    #[test]
+    fn parse_accepts_hashes_and_paths() {
+        assert_eq!(
+            ResourceHash::to_u64(&ResourceHash::parse("00290D5B143172A3").unwrap()),
+            TEST_IMAGE_HASH
+        );
+        assert_eq!(
+            ResourceHash::to_u64(&ResourceHash::parse("0x00290d5b143172a3").unwrap()),
+            TEST_IMAGE_HASH
+        );
+        assert_eq!(
+            ResourceHash::to_u64(
+                &ResourceHash::parse("[assembly:/_test/lib.a?/test_image.png].pc_webp").unwrap()
+            ),
+            TEST_IMAGE_HASH
+        );
+        assert_eq!(
+            ResourceHash::parse("not a hash").unwrap_err().code,
+            NavKitErrorCode::InvalidHash
+        );
+    }
+}
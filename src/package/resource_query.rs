@@ -0,0 +1,55 @@
+#[derive(Debug, Clone)]
+pub enum PathPattern {
+    // `*` matches any run of characters and `?` a single character. Matched case-insensitively
+    // against the whole resource path.
+    Glob(String),
+    // Matched anywhere in the resource path.
+    Regex(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ResourceQuery {
+    // Resource types such as "NAVP" or "AIRG". Empty matches every type.
+    pub resource_types: Vec<String>,
+    // Matched against paths from the loaded hash list. Resources without a known path never match.
+    pub path_pattern: Option<PathPattern>,
+    // Partition ids such as "chunk0" or "dlc5". Empty matches every mounted partition.
+    pub partitions: Vec<String>,
+    // Inclusive bounds on the uncompressed resource size in bytes.
+    pub min_size: u64,
+    pub max_size: u64,
+}
+
+impl Default for ResourceQuery {
+    fn default() -> Self {
+        Self {
+            resource_types: Vec::new(),
+            path_pattern: None,
+            partitions: Vec::new(),
+            min_size: 0,
+            max_size: u64::MAX,
+        }
+    }
+}
+
+impl ResourceQuery {
+    pub fn includes_type(&self, resource_type: &str) -> bool {
+        self.resource_types.is_empty()
+            || self
+                .resource_types
+                .iter()
+                .any(|included_type| included_type.eq_ignore_ascii_case(resource_type))
+    }
+
+    pub fn includes_partition(&self, partition_id: &str) -> bool {
+        self.partitions.is_empty()
+            || self
+                .partitions
+                .iter()
+                .any(|partition| partition.eq_ignore_ascii_case(partition_id))
+    }
+
+    pub fn includes_size(&self, size: u32) -> bool {
+        (self.min_size..=self.max_size).contains(&(size as u64))
+    }
+}
@@ -0,0 +1,139 @@
+use regex::{Regex, RegexBuilder};
+use rpkg_rs::resource::partition_manager::PartitionManager;
+use rpkg_rs::resource::runtime_resource_id::RuntimeResourceID;
+
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::extract::extraction_manifest::ManifestEntry;
+use crate::logging::logger::Logger;
+use crate::package::resource_hash::ResourceHash;
+use crate::package::resource_query::{PathPattern, ResourceQuery};
+use crate::package::resource_type::ResourceType;
+
+#[derive(Debug, Clone)]
+pub struct ResourceSearchHit {
+    pub hash: RuntimeResourceID,
+    pub resource_type: String,
+    // Partition id, such as "chunk0".
+    pub partition: String,
+    // Package file holding the latest version of the resource in its partition.
+    pub package: String,
+    // 0 for the base package, otherwise the patch number.
+    pub patch_id: usize,
+    pub size: u32,
+    // Only known when a hash list is loaded.
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ResourceSearchResult {
+    pub hits: Vec<ResourceSearchHit>,
+}
+
+pub struct ResourceSearch;
+
+impl ResourceSearch {
+    // Returns one hit per matching resource and partition. Hits are ordered by partition mount
+    // order, then by hash.
+    pub fn search(
+        partition_manager: &PartitionManager,
+        query: &ResourceQuery,
+        logger: &Logger,
+    ) -> Result<ResourceSearchResult, NavKitError> {
+        let logger = logger.with_module(module_path!());
+        if let Some(resource_type) = query
+            .resource_types
+            .iter()
+            .find(|resource_type| !ResourceType::is_well_formed(resource_type))
+        {
+            return Err(NavKitError::new(
+                NavKitErrorCode::InvalidResourceType,
+                format!(
+                    "Invalid resource type '{}': expected four letters or digits",
+                    resource_type
+                ),
+            )
+            .log(&logger));
+        }
+        let path_regex = match &query.path_pattern {
+            Some(path_pattern) => {
+                if !ResourceHash::paths_available() {
+                    logger.warn(
+                        "No hash list is loaded, so no resource path can match the path pattern",
+                    );
+                }
+                Some(Self::compile_path_pattern(path_pattern).map_err(|e| e.log(&logger))?)
+            }
+            None => None,
+        };
+
+        let mut hits = Vec::new();
+        for partition in partition_manager.partitions() {
+            let partition_info = partition.partition_info();
+            let partition_id = partition_info.id().to_string();
+            if !query.includes_partition(&partition_id) {
+                continue;
+            }
+            let mut partition_hits = partition
+                .latest_resources()
+                .into_iter()
+                .filter(|(resource, _)| {
+                    query.includes_type(&resource.data_type())
+                        && query.includes_size(resource.size())
+                })
+                .filter_map(|(resource, patch_id)| {
+                    let path = ResourceHash::path_of(resource.rrid());
+                    if let Some(path_regex) = &path_regex {
+                        if !path
+                            .as_deref()
+                            .is_some_and(|path| path_regex.is_match(path))
+                        {
+                            return None;
+                        }
+                    }
+                    Some(ResourceSearchHit {
+                        hash: *resource.rrid(),
+                        resource_type: resource.data_type(),
+                        partition: partition_id.clone(),
+                        package: partition_info.filename(patch_id),
+                        patch_id: ManifestEntry::patch_number(patch_id),
+                        size: resource.size(),
+                        path,
+                    })
+                })
+                .collect::<Vec<_>>();
+            partition_hits.sort_by_key(|hit| ResourceHash::to_u64(&hit.hash));
+            hits.append(&mut partition_hits);
+        }
+        logger.info(format!("Found {} matching resources.", hits.len()));
+        Ok(ResourceSearchResult { hits })
+    }
+
+    fn compile_path_pattern(path_pattern: &PathPattern) -> Result<Regex, NavKitError> {
+        let (pattern, case_insensitive) = match path_pattern {
+            PathPattern::Glob(glob) => (Self::glob_to_regex(glob), true),
+            PathPattern::Regex(regex) => (regex.clone(), false),
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|e| {
+                NavKitError::new(
+                    NavKitErrorCode::InvalidSearchPattern,
+                    format!("Invalid path pattern '{}': {}", pattern, e),
+                )
+            })
+    }
+
+    fn glob_to_regex(glob: &str) -> String {
+        let mut regex = String::from("^");
+        for c in glob.chars() {
+            match c {
+                '*' => regex.push_str(".*"),
+                '?' => regex.push('.'),
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+        regex
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+use rpkg_rs::resource::partition_manager::PartitionManager;
+
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::logging::logger::Logger;
+
+// The resource types of each partition manager mounted through the C API, keyed by its address.
+// Entries are added once the partitions are mounted and removed when the manager is freed.
+static MOUNTED_TYPES: Mutex<BTreeMap<usize, Arc<BTreeSet<String>>>> = Mutex::new(BTreeMap::new());
+
+pub struct ResourceType;
+
+impl ResourceType {
+    // Resource types are four ASCII letters or digits, such as "ALOC" or "TEXD".
+    pub fn is_well_formed(resource_type: &str) -> bool {
+        resource_type.len() == 4 && resource_type.bytes().all(|b| b.is_ascii_alphanumeric())
+    }
+
+    pub fn types_in_partitions(partition_manager: &PartitionManager) -> BTreeSet<String> {
+        partition_manager
+            .partitions()
+            .iter()
+            .flat_map(|partition| {
+                partition
+                    .latest_resources()
+                    .into_iter()
+                    .map(|(resource, _)| resource.data_type())
+            })
+            .collect()
+    }
+
+    pub fn remember_mounted(partition_manager: &PartitionManager) {
+        let resource_types = Arc::new(Self::types_in_partitions(partition_manager));
+        MOUNTED_TYPES.lock().unwrap().insert(
+            partition_manager as *const PartitionManager as usize,
+            resource_types,
+        );
+    }
+
+    pub fn forget_mounted(partition_manager: *const PartitionManager) {
+        MOUNTED_TYPES
+            .lock()
+            .unwrap()
+            .remove(&(partition_manager as usize));
+    }
+
+    // The types remembered for `partition_manager`, or the types found by walking its partitions
+    // if it was not mounted through the C API.
+    pub fn mounted_types(partition_manager: &PartitionManager) -> Arc<BTreeSet<String>> {
+        let remembered_types = MOUNTED_TYPES
+            .lock()
+            .unwrap()
+            .get(&(partition_manager as *const PartitionManager as usize))
+            .cloned();
+        remembered_types.unwrap_or_else(|| Arc::new(Self::types_in_partitions(partition_manager)))
+    }
+
+    // Returns the upper-case form of `resource_type` if it is well formed.
+    pub fn parse(resource_type: &str, logger: &Logger) -> Result<String, NavKitError> {
+        let logger = logger.with_module(module_path!());
+        if !Self::is_well_formed(resource_type) {
+            return Err(NavKitError::new(
+                NavKitErrorCode::InvalidResourceType,
+                format!(
+                    "Invalid resource type '{}': expected four letters or digits",
+                    resource_type
+                ),
+            )
+            .log(&logger));
+        }
+        Ok(resource_type.to_ascii_uppercase())
+    }
+
+    // Fails if no mounted partition contains a resource of the upper-case `resource_type`.
+    pub fn check_mounted(
+        resource_type: &str,
+        partition_manager: &PartitionManager,
+        logger: &Logger,
+    ) -> Result<(), NavKitError> {
+        let logger = logger.with_module(module_path!());
+        let available_types = Self::mounted_types(partition_manager);
+        if !available_types.contains(resource_type) {
+            return Err(NavKitError::new(
+                NavKitErrorCode::InvalidResourceType,
+                format!(
+                    "Unknown resource type '{}'. Mounted partitions contain: {}",
+                    resource_type,
+                    available_types
+                        .iter()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )
+            .log(&logger));
+        }
+        Ok(())
+    }
+}
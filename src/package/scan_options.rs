@@ -0,0 +1,47 @@
+use rpkg_rs::resource::pdefs::PartitionInfo;
+
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    pub max_patch_level: usize,
+    // When set, each partition keeps the patch level from packagedefinition.txt, capped at
+    // `max_patch_level`. Otherwise every partition is raised to `max_patch_level`.
+    // Conditions in packagedefinition.txt are not evaluated: rpkg-rs drops them while parsing, so
+    // every listed partition is mounted unless `included_partitions` or `excluded_partitions`
+    // rule it out.
+    pub keep_package_definition_patch_levels: bool,
+    // Partition ids such as "chunk0" or "dlc5langjp". `None` mounts every partition.
+    pub included_partitions: Option<Vec<String>>,
+    pub excluded_partitions: Vec<String>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            max_patch_level: 301,
+            keep_package_definition_patch_levels: false,
+            included_partitions: None,
+            excluded_partitions: Vec::new(),
+        }
+    }
+}
+
+impl ScanOptions {
+    pub fn includes_partition(&self, partition: &PartitionInfo) -> bool {
+        let partition_id = partition.id().to_string();
+        let matches = |id: &String| id.eq_ignore_ascii_case(&partition_id);
+        if let Some(included_partitions) = &self.included_partitions {
+            if !included_partitions.iter().any(matches) {
+                return false;
+            }
+        }
+        !self.excluded_partitions.iter().any(matches)
+    }
+
+    pub fn patch_level_for(&self, partition: &PartitionInfo) -> usize {
+        if self.keep_package_definition_patch_levels {
+            partition.max_patch_level().min(self.max_patch_level)
+        } else {
+            self.max_patch_level
+        }
+    }
+}
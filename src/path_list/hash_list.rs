@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use rayon::prelude::*;
+use rpkg_rs::resource::runtime_resource_id::RuntimeResourceID;
+
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use crate::logging::logger::Logger;
+use crate::package::resource_hash::ResourceHash;
+
+#[derive(Debug, Clone)]
+pub struct HashListEntry {
+    pub hash: RuntimeResourceID,
+    pub resource_type: String,
+    pub path: Option<String>,
+}
+
+// A community hash list mapping runtime resource ids to their type and, where known, their
+// resource path. Lines look like `00546F0BD4E80484.GFXI,[assembly:/...].pc_gfx`; lines starting
+// with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct HashList {
+    entries: HashMap<RuntimeResourceID, HashListEntry>,
+    hashes_by_path: HashMap<String, RuntimeResourceID>,
+}
+
+static REGISTERED_HASH_LIST: RwLock<Option<Arc<HashList>>> = RwLock::new(None);
+
+impl HashList {
+    pub fn load(path: &Path, logger: &Logger) -> Result<Self, NavKitError> {
+        let logger = logger.with_module(module_path!());
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                return Err(NavKitError::new(
+                    NavKitErrorCode::HashListRead,
+                    format!("Failed to read hash list {}: {}", path.display(), e),
+                )
+                .log(&logger));
+            }
+        };
+        let hash_list = Self::parse(&contents);
+        logger.info(format!(
+            "Loaded {} hashes, {} with paths, from {}.",
+            hash_list.entries.len(),
+            hash_list.hashes_by_path.len(),
+            path.display()
+        ));
+        Ok(hash_list)
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        let entries = contents
+            .par_lines()
+            .filter_map(Self::parse_line)
+            .collect::<Vec<HashListEntry>>();
+        let hashes_by_path = entries
+            .par_iter()
+            .filter_map(|entry| {
+                let path = entry.path.as_ref()?;
+                let normalized_path = ResourceHash::normalize_resource_path(path).ok()?;
+                Some((normalized_path, entry.hash))
+            })
+            .collect();
+        Self {
+            entries: entries
+                .into_iter()
+                .map(|entry| (entry.hash, entry))
+                .collect(),
+            hashes_by_path,
+        }
+    }
+
+    fn parse_line(line: &str) -> Option<HashListEntry> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (hash_and_type, path) = match line.split_once(',') {
+            Some((hash_and_type, path)) => (hash_and_type, Some(path.trim())),
+            None => (line, None),
+        };
+        let (hash, resource_type) = hash_and_type.split_once('.').unwrap_or((hash_and_type, ""));
+        if hash.len() != 16 {
+            return None;
+        }
+        let hash = RuntimeResourceID::from_hex_string(hash).ok()?;
+        Some(HashListEntry {
+            hash,
+            resource_type: resource_type.to_string(),
+            path: path
+                .filter(|path| !path.is_empty())
+                .map(|path| path.to_string()),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entry(&self, hash: &RuntimeResourceID) -> Option<&HashListEntry> {
+        self.entries.get(hash)
+    }
+
+    pub fn path(&self, hash: &RuntimeResourceID) -> Option<&str> {
+        self.entries.get(hash)?.path.as_deref()
+    }
+
+    pub fn hash(&self, resource_path: &str) -> Option<RuntimeResourceID> {
+        let normalized_path = ResourceHash::normalize_resource_path(resource_path).ok()?;
+        self.hashes_by_path.get(&normalized_path).copied()
+    }
+
+    // The registered list is used to annotate log messages, manifests and listings.
+    pub fn register(hash_list: HashList) {
+        *REGISTERED_HASH_LIST.write().unwrap() = Some(Arc::new(hash_list));
+    }
+
+    pub fn unregister() {
+        *REGISTERED_HASH_LIST.write().unwrap() = None;
+    }
+
+    pub fn registered() -> Option<Arc<HashList>> {
+        REGISTERED_HASH_LIST.read().unwrap().clone()
+    }
+}
@@ -0,0 +1 @@
+pub mod hash_list;
\ No newline at end of file
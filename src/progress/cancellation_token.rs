@@ -0,0 +1,35 @@
+use crate::error::navkit_error::{NavKitError, NavKitErrorCode};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Default)]
+pub struct CancellationToken {
+    cancelled: AtomicBool,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn check(cancellation_token: Option<&CancellationToken>) -> Result<(), NavKitError> {
+        match cancellation_token {
+            Some(token) if token.is_cancelled() => Err(NavKitError::new(
+                NavKitErrorCode::Cancelled,
+                "Operation was cancelled",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
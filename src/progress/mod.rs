@@ -0,0 +1,2 @@
+pub mod cancellation_token;
+pub mod progress_report;
\ No newline at end of file
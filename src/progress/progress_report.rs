@@ -0,0 +1,53 @@
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::sync::Mutex;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressPhase {
+    MountPartitions,
+    ExtractResources,
+}
+
+// Spelled out as `Option<extern "C" fn(...)>` in exported functions, like the logger callbacks,
+// so the generated header gets a plain nullable function pointer.
+pub type ProgressCallback = extern "C" fn(ProgressPhase, usize, usize, *const c_char);
+
+// Serializes callback invocations so hosts receive monotonically increasing values even when
+// workers on several threads report at once.
+pub struct ProgressReport {
+    callback: Option<ProgressCallback>,
+    phase: ProgressPhase,
+    total: usize,
+    current: Mutex<usize>,
+}
+
+impl ProgressReport {
+    pub fn new(callback: Option<ProgressCallback>, phase: ProgressPhase, total: usize) -> Self {
+        Self {
+            callback,
+            phase,
+            total,
+            current: Mutex::new(0),
+        }
+    }
+
+    pub fn report(&self, current: usize, partition_name: &str) {
+        let mut current_ref = self.current.lock().unwrap();
+        *current_ref = current;
+        self.send(*current_ref, partition_name);
+    }
+
+    pub fn advance(&self, partition_name: &str) {
+        let mut current_ref = self.current.lock().unwrap();
+        *current_ref += 1;
+        self.send(*current_ref, partition_name);
+    }
+
+    fn send(&self, current: usize, partition_name: &str) {
+        if let Some(callback) = self.callback {
+            let name = CString::new(partition_name).unwrap_or_default();
+            callback(self.phase, current, self.total, name.as_ptr());
+        }
+    }
+}